@@ -1,6 +1,10 @@
 use crate::Cli;
 use futures::StreamExt;
-use licensebat_core::{licrc::LicRc, FileCollector, RetrievedDependency};
+use licensebat_core::{
+    collector::DEFAULT_CONCURRENCY,
+    licrc::{LicRc, DEFAULT_MIN_DETECTION_CONFIDENCE},
+    CollectorRegistry, DependencyGraph, RetrievedDependency,
+};
 use std::sync::Arc;
 
 const LICENSE_CACHE: &[u8] = std::include_bytes!("../license-cache.bin.zstd");
@@ -9,6 +13,8 @@ const LICENSE_CACHE: &[u8] = std::include_bytes!("../license-cache.bin.zstd");
 enum CheckError {
     #[error("Error reading dependency file: {0}")]
     DependencyFile(#[from] std::io::Error),
+    #[error("No collector found for dependency file {0}")]
+    NoCollector(String),
 }
 
 /// Result of the dependency validation.
@@ -17,6 +23,8 @@ pub struct RunResult {
     pub licrc: LicRc,
     /// The validated dependencies.
     pub dependencies: Vec<RetrievedDependency>,
+    /// The dependency graph of the project. It will be empty if the collector doesn't support it.
+    pub graph: DependencyGraph,
 }
 
 /// Checks the dependencies of a project.
@@ -29,9 +37,12 @@ pub struct RunResult {
 /// - Reading a dependency manifest file (package-lock.json, yarn.lock, etc.)
 /// - Reading the .licrc file
 pub async fn run(cli: Cli) -> anyhow::Result<RunResult> {
+    let dependency_file = cli
+        .dependency_file
+        .ok_or_else(|| anyhow::anyhow!("A dependency file must be provided"))?;
     tracing::info!(
-        dependency_file = %cli.dependency_file,
-        "Licensebat running! Using {}", cli.dependency_file
+        dependency_file = %dependency_file,
+        "Licensebat running! Using {}", dependency_file
     );
 
     // 0. spdx store & http client
@@ -49,37 +60,45 @@ pub async fn run(cli: Cli) -> anyhow::Result<RunResult> {
 
     // 2. get content of the dependency file
     tracing::debug!("Getting dependency file content");
-    let dep_file_content = get_dep_file_content(&cli.dependency_file).await?;
+    let dep_file_content = get_dep_file_content(&dependency_file).await?;
 
     // 3. create collectors
     tracing::debug!("Building collectors");
+    let min_detection_confidence = licrc
+        .behavior
+        .min_detection_confidence
+        .unwrap_or(DEFAULT_MIN_DETECTION_CONFIDENCE);
     let npm_retriever = licensebat_js::retriever::Npm::new(client.clone());
     let npm_collector = licensebat_js::collector::Npm::new(npm_retriever.clone());
     let yarn_collector = licensebat_js::collector::Yarn::new(npm_retriever);
-    let rust_collector =
-        licensebat_rust::collector::Rust::with_docs_rs_retriever(client.clone(), store.clone());
-    let dart_collector = licensebat_dart::collector::Dart::with_hosted_retriever(client, store);
+    let rust_collector = licensebat_rust::collector::Rust::new(
+        licensebat_rust::retriever::DocsRs::new(client.clone(), store.clone())
+            .with_min_detection_confidence(min_detection_confidence),
+    );
+    let dart_collector = licensebat_dart::collector::Dart::new(
+        licensebat_dart::retriever::Hosted::new(client, store)
+            .with_min_detection_confidence(min_detection_confidence),
+    );
 
-    let file_collectors: Vec<Box<dyn FileCollector>> = vec![
-        Box::new(npm_collector),
-        Box::new(yarn_collector),
-        Box::new(rust_collector),
-        Box::new(dart_collector),
-    ];
+    let registry = CollectorRegistry::new()
+        .with(npm_collector)
+        .with(yarn_collector)
+        .with(rust_collector)
+        .with(dart_collector);
 
     // 4. get dependency stream
-    let mut stream = file_collectors
-        .iter()
-        .find(|c| cli.dependency_file.contains(&c.get_dependency_filename()))
-        .and_then(|c| c.get_dependencies(&dep_file_content, &licrc).ok())
-        .expect(
-            format!(
-                "No collector found for dependency file {}",
-                cli.dependency_file
-            )
-            .as_str(),
-        )
-        .buffer_unordered(licrc.behavior.retriever_buffer_size.unwrap_or(100));
+    let collector = registry
+        .resolve(&dependency_file)
+        .ok_or_else(|| CheckError::NoCollector(dependency_file.clone()))?;
+    tracing::debug!(collector = %collector.get_name(), "Collector found");
+    let mut stream = collector
+        .get_dependencies(&dep_file_content, &licrc)?
+        .with_concurrency(
+            licrc
+                .behavior
+                .retriever_buffer_size
+                .unwrap_or(DEFAULT_CONCURRENCY),
+        );
 
     // 5. validate the dependencies according to the .licrc config
     tracing::debug!("Validating dependencies");
@@ -91,10 +110,15 @@ pub async fn run(cli: Cli) -> anyhow::Result<RunResult> {
         validated_deps.push(dependency);
     }
 
+    // 6. build the dependency graph
+    tracing::debug!("Building dependency graph");
+    let graph = collector.get_dependency_graph(&dep_file_content)?;
+
     tracing::info!("Done!");
     Ok(RunResult {
         licrc,
         dependencies: validated_deps,
+        graph,
     })
 }
 
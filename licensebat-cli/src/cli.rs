@@ -17,14 +17,34 @@ use structopt::StructOpt;
 pub struct Cli {
     /// Path to the file containing the dependencies of the project.
     /// i.e. package-lock.json for npm projects, yarn.lock for yarn projects, etc.
+    /// It's required unless a subcommand is used.
     #[structopt(short, long)]
-    pub dependency_file: String,
+    pub dependency_file: Option<String>,
     /// Path to the .licrc file
-    #[structopt(short, long, default_value = ".licrc")]
+    #[structopt(short, long, default_value = ".licrc", global = true)]
     pub licrc_file: String,
-    /// Output format (json | markdown). Defaults to json.
+    /// Output format (json | markdown | sarif | cyclonedx | cyclonedx-xml | spdx-json | spdx-tv | junit | html). Defaults to json.
     #[structopt(short = "f", long, default_value = "json")]
     pub output_format: OutputFormat,
+    /// Subcommand to run instead of checking the dependencies.
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands of the CLI.
+#[derive(Debug, StructOpt, Clone)]
+pub enum Command {
+    /// Manages the .licrc file.
+    Config(ConfigCommand),
+}
+
+/// Subcommands to manage the .licrc file.
+#[derive(Debug, StructOpt, Clone)]
+pub enum ConfigCommand {
+    /// Prints the fully resolved .licrc file, once all the policies it extends have been merged.
+    Show,
+    /// Checks the .licrc file and the policies it extends, reporting unknown keys, invalid values and non SPDX licenses.
+    Check,
 }
 
 /// Format of the CLIs output
@@ -34,15 +54,55 @@ pub enum OutputFormat {
     Json,
     /// Markdown format
     Markdown,
+    /// SARIF 2.1.0 format
+    Sarif,
+    /// CycloneDX SBOM in JSON format
+    CycloneDxJson,
+    /// CycloneDX SBOM in XML format
+    CycloneDxXml,
+    /// SPDX 2.3 document in JSON format
+    SpdxJson,
+    /// SPDX 2.3 document in tag-value format
+    SpdxTagValue,
+    /// JUnit XML report
+    Junit,
+    /// Self-contained HTML report
+    Html,
 }
 
 impl FromStr for OutputFormat {
-    type Err = &'static str;
+    type Err = std::convert::Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "json" => Ok(Self::Json),
             "markdown" | "md" => Ok(Self::Markdown),
-            _ => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            "cyclonedx" | "cyclonedx-json" => Ok(Self::CycloneDxJson),
+            "cyclonedx-xml" => Ok(Self::CycloneDxXml),
+            "spdx-json" => Ok(Self::SpdxJson),
+            "spdx-tv" => Ok(Self::SpdxTagValue),
+            "junit" => Ok(Self::Junit),
+            "html" => Ok(Self::Html),
+            _ => {
+                tracing::warn!(
+                    format = s,
+                    "Unknown output format, using json. Valid values are json, markdown, sarif, cyclonedx, cyclonedx-xml, spdx-json, spdx-tv, junit and html"
+                );
+                Ok(Self::Json)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_output_formats_fall_back_to_json() {
+        assert!(matches!("md".parse(), Ok(OutputFormat::Markdown)));
+        assert!(matches!("sarif".parse(), Ok(OutputFormat::Sarif)));
+        assert!(matches!("jsno".parse(), Ok(OutputFormat::Json)));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::ConfigCommand;
+use licensebat_core::licrc::LicRc;
+
+/// Result of a [`ConfigCommand`].
+pub struct ConfigResult {
+    /// Text to be printed.
+    pub output: String,
+    /// False if the command has found any error.
+    pub success: bool,
+}
+
+/// Runs a [`ConfigCommand`].
+///
+/// # Errors
+///
+/// Errors can be caused by reading or parsing the .licrc file and the policies it extends.
+/// Note that `check` reports the problems found in the files in its output instead.
+pub fn run_config(licrc_file: &str, command: &ConfigCommand) -> anyhow::Result<ConfigResult> {
+    match command {
+        ConfigCommand::Show => {
+            let licrc = LicRc::from_relative_path(licrc_file)?;
+            Ok(ConfigResult {
+                output: toml::to_string_pretty(&licrc)?,
+                success: true,
+            })
+        }
+        ConfigCommand::Check => {
+            let diagnostics = LicRc::check_relative_path(licrc_file)?;
+            let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+            let warnings = diagnostics.len() - errors;
+            let mut output = diagnostics
+                .iter()
+                .map(|d| format!("{d}\n\n"))
+                .collect::<String>();
+            if diagnostics.is_empty() {
+                output.push_str(&format!("{licrc_file} is valid"));
+            } else {
+                output.push_str(&format!(
+                    "{licrc_file}: {errors} error(s), {warnings} warning(s)"
+                ));
+            }
+            Ok(ConfigResult {
+                output,
+                success: errors == 0,
+            })
+        }
+    }
+}
@@ -46,9 +46,26 @@
 //!     -d, --dependency-file <dependency-file>    Path to the file containing the dependencies of the project. i.e.
 //!                                                package-lock.json for npm projects, yarn.lock for yarn projects, etc
 //!     -l, --licrc-file <licrc-file>              Path to the .licrc file [default: .licrc]
-//!     -f, --output-format <output-format>        Output format (json | markdown). Defaults to json.
+//!     -f, --output-format <output-format>        Output format (json | markdown | sarif | cyclonedx |
+//!                                                cyclonedx-xml | spdx-json | spdx-tv | junit |
+//!                                                html). Defaults to json.
 //! ```
 //!
+//! Use `--output-format sarif` to get a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log that can be uploaded to GitHub code scanning.
+//! Every invalid dependency that is not ignored is reported as a result pointing at the dependency file.
+//!
+//! Use `--output-format cyclonedx` (or `cyclonedx-xml`) to get a [CycloneDX](https://cyclonedx.org/) SBOM with every dependency,
+//! its Package URL, its licenses and the result of the validation as `licensebat:*` properties.
+//!
+//! Use `--output-format spdx-json` (or `spdx-tv` for the tag-value format) to get an [SPDX 2.3](https://spdx.github.io/spdx-spec/v2.3/) document
+//! with a package for every dependency and the relationships found in the dependency file.
+//!
+//! Use `--output-format junit` to get a JUnit XML report that your CI server can show in its test tab.
+//! Every dependency is a test case: invalid ones are failures and ignored ones are skipped.
+//!
+//! Use `--output-format html` to get a single static HTML file with summary counts, a filterable and sortable table
+//! of the dependencies and the dependencies grouped by license.
+//!
 //! ## The .licrc file
 //!
 //! But before running, you have to be sure you have a `.licrc` file available in your project.
@@ -66,15 +83,62 @@
 //! # The rest will be accepted, except for the unknown licenses or dependencies without licenses.
 //! # unaccepted = ["LGPL"]
 //! # Note that only one of the previous options can be enabled at once.
-//! # If both of them are informed, only accepted will be considered.
+//! # Informing both of them is an error.
+//! #
+//! # Licenses declared as SPDX expressions (i.e. `MIT OR Apache-2.0`) are evaluated term by term:
+//! # OR needs any of its licenses to be accepted while AND needs all of them.
+//! # `WITH` exceptions are covered by their base license and `GPL-2.0-or-later` (or `GPL-2.0+`) is covered by any later version.
+//! # Unaccepted and warned licenses only apply to `GPL-2.0-or-later` if they cover every version it allows (i.e. `GPL-2.0-or-later` itself).
+//! #
+//! # Licenses can also be accepted or rejected by category: `permissive`, `public-domain`, `weak-copyleft`,
+//! # `strong-copyleft`, `network-copyleft` and `proprietary`.
+//! # Explicit licenses take precedence over categories and denied categories take precedence over allowed ones.
+//! # allowed_categories = ["permissive", "public-domain"]
+//! # denied_categories = ["strong-copyleft", "network-copyleft"]
+//! #
+//! # Warned licenses (and categories) will be reported as warnings but they won't make the check fail.
+//! # Only denied dependencies make the check fail.
+//! # warned = ["LGPL-3.0-only"]
+//! # warned_categories = ["weak-copyleft"]
+//!
+//! [licenses.aliases]
+//! # Licenses are normalized to SPDX identifiers before being validated (i.e. `Apache 2.0` => `Apache-2.0` or `MIT/Apache-2.0` => `MIT OR Apache-2.0`).
+//! # Licensebat comes with a built-in alias table but you can add your own aliases here.
+//! "Acme Commercial License" = "LicenseRef-Acme"
+//!
+//! [licenses.categories]
+//! # Licensebat classifies the SPDX licenses in categories, but you can add your own classification here.
+//! "LicenseRef-Acme" = "proprietary"
+//!
+//! [licenses.dev]
+//! # Dev dependencies can have their own policy (there are [licenses.optional] and [licenses.transitive] sections for optional and transitive dependencies too).
+//! # It takes the same lists as [licenses] except aliases and categories, which are shared.
+//! # Lists that are not informed are inherited from the main policy (accepted and unaccepted are inherited together).
+//! # The policy used to validate each dependency is shown in the report.
+//! unaccepted = ["AGPL-3.0-only"]
 //!
 //! [dependencies]
 //! # This will allow users to flag some dependencies so that Licensebat will not check for their license.
-//! ignored=["ignored_dep1", "ignored_dep2"]
+//! # Entries can use `*` wildcards and a semver version requirement after `@` (i.e. `@acme/*`, `acme-*`, `openssl@<0.11` or `name@=1.2.3`).
+//! ignored=["ignored_dep1", "ignored_dep2", "@acme/*", "openssl@<0.11"]
+//! # Entries can also be justified exceptions. They will be honoured until the end of their expiry date,
+//! # then the dependency will be validated again and reported as an expired exception.
+//! # ignored = [
+//! #   "ignored_dep1",
+//! #   { name = "openssl@<0.11", reason = "Only used in tests", approved_by = "legal@acme.com", expires = 2027-01-31 },
+//! # ]
 //! # If set to true, Licensebat will ignore the dev dependencies.
 //! ignore_dev_dependencies = true
 //! # If set to true, Licensebat will ignore the optional dependencies.
 //! ignore_optional_dependencies = true
+//! # If set to true, Licensebat will ignore the transitive dependencies, so the policy is only enforced on direct dependencies.
+//! ignore_transitive_dependencies = false
+//!
+//! [dependencies.overrides]
+//! # This will allow users to declare the license of some dependencies instead of retrieving it from their sources.
+//! # Keys can be the name of the dependency or `name@version`. These dependencies will be validated as any other.
+//! my_git_crate = "MIT"
+//! "my_other_crate@1.2.3" = "MIT OR Apache-2.0"
 //!
 //! [behavior]
 //! # False by default (always exit code == 0), if true, it will exit with code 1 in case some invalid dependency is found.
@@ -83,8 +147,47 @@
 //! # It's set to 100 by default.
 //! # If you have a lot of dependencies, you might want to increase this value, but be careful, if the size is too big, the API might return an error.
 //! retriever_buffer_size: 100,
+//! # Licenses detected by analyzing the text of a license file (i.e. when a crate only declares a `license-file`)
+//! # are only trusted if the confidence of the detection is at least this value (0.8 by default).
+//! # Otherwise, the dependency will be flagged for review with the suggested licenses.
+//! min_detection_confidence = 0.8
+//! ```
+//!
+//! ### Sharing policies
+//!
+//! A `.licrc` file can extend other `.licrc` files, so several repositories can share the same base policy:
+//!
+//! ```toml
+//! extends = ["../policies/base.licrc"]
+//!
+//! [licenses]
+//! # adds ISC to the inherited accepted licenses and removes GPL-3.0-only from them
+//! accepted = ["ISC", "!GPL-3.0-only"]
 //! ```
 //!
+//! Paths are relative to the file declaring them. Policies are merged in order and the file itself is applied last:
+//!
+//! - Lists are merged. Entries prefixed with `!` remove the inherited entry.
+//! - Tables are merged key by key. Keys prefixed with `!` remove the inherited key (i.e. `"!my_crate" = ""` in `[dependencies.overrides]`).
+//! - Any other value overrides the inherited one.
+//!
+//! You can print the fully resolved policy by running:
+//!
+//! ```bash
+//! licensebat config show --licrc-file .licrc
+//! ```
+//!
+//! ### Checking the .licrc file
+//!
+//! Unknown keys, invalid values or setting both `accepted` and `unaccepted` make Licensebat fail instead of silently falling back to a permissive policy.
+//! You can check your `.licrc` file (and the policies it extends) by running:
+//!
+//! ```bash
+//! licensebat config check --licrc-file .licrc
+//! ```
+//!
+//! It will point at the offending lines, suggest the right keys in case of typos and warn you about licenses that are not valid [SPDX identifiers](https://spdx.org/licenses/).
+//!
 //! ## Logs
 //!
 //! `Licensebat` uses [`tracing`](https://docs.rs/tracing). You can get logs while running the `CLI` by setting the `RUST_LOG` environment variable.
@@ -99,7 +202,10 @@
 
 mod check;
 mod cli;
+mod config;
+pub mod output;
 
 pub use check::{run, RunResult};
 #[doc(hidden)]
-pub use cli::{Cli, OutputFormat};
+pub use cli::{Cli, Command, ConfigCommand, OutputFormat};
+pub use config::{run_config, ConfigResult};
@@ -3,8 +3,8 @@
 #![doc(html_favicon_url = "https://licensebat.com/images/not_used/favicons_red/favicon.ico")]
 #![warn(missing_docs)]
 
-use licensebat_cli::OutputFormat;
-use licensebat_core::RetrievedDependency;
+use licensebat_cli::{Command, OutputFormat};
+use licensebat_core::{DependencyGraph, RetrievedDependency, Verdict};
 use structopt::StructOpt;
 
 #[tokio::main]
@@ -12,26 +12,95 @@ async fn main() -> anyhow::Result<()> {
     dotenv::dotenv().ok();
     set_up_tracing();
     let cli = licensebat_cli::Cli::from_args();
+    if let Some(Command::Config(command)) = &cli.command {
+        let result = licensebat_cli::run_config(&cli.licrc_file, command)?;
+        println!("{}", result.output);
+        if !result.success {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     let format = cli.output_format.clone();
+    let dependency_file = cli.dependency_file.clone().unwrap_or_default();
+    let document_name = std::path::Path::new(&dependency_file)
+        .file_name()
+        .map_or_else(
+            || dependency_file.clone(),
+            |name| name.to_string_lossy().into_owned(),
+        );
     let licensebat_cli::RunResult {
         licrc,
         mut dependencies,
+        graph,
     } = licensebat_cli::run(cli).await?;
 
-    let invalid_dependencies_count = dependencies
-        .iter()
-        .filter(|d| !d.is_valid && !d.is_ignored)
-        .count();
+    let count = |verdict: Verdict| {
+        dependencies
+            .iter()
+            .filter(|d| d.verdict == verdict && !d.is_ignored)
+            .count()
+    };
+    let invalid_dependencies_count = count(Verdict::Deny);
+    let warned_dependencies_count = count(Verdict::Warn);
 
-    // output to json or markdown
+    // output to the selected format
     match format {
         OutputFormat::Json => show_result_as_json(&dependencies)?,
+        OutputFormat::Sarif => {
+            tracing::debug!("Showing results as SARIF");
+            println!(
+                "{}",
+                licensebat_cli::output::to_sarif(&dependencies, &dependency_file)?
+            );
+        }
+        OutputFormat::CycloneDxJson => {
+            tracing::debug!("Showing results as CycloneDX JSON");
+            println!(
+                "{}",
+                licensebat_cli::output::to_cyclonedx_json(&dependencies)?
+            );
+        }
+        OutputFormat::CycloneDxXml => {
+            tracing::debug!("Showing results as CycloneDX XML");
+            print!(
+                "{}",
+                licensebat_cli::output::to_cyclonedx_xml(&dependencies)
+            );
+        }
+        OutputFormat::SpdxJson => {
+            tracing::debug!("Showing results as SPDX JSON");
+            println!(
+                "{}",
+                licensebat_cli::output::to_spdx_json(&dependencies, &graph, &document_name)?
+            );
+        }
+        OutputFormat::SpdxTagValue => {
+            tracing::debug!("Showing results as SPDX tag-value");
+            print!(
+                "{}",
+                licensebat_cli::output::to_spdx_tag_value(&dependencies, &graph, &document_name)
+            );
+        }
+        OutputFormat::Junit => {
+            tracing::debug!("Showing results as JUnit");
+            print!("{}", licensebat_cli::output::to_junit(&dependencies));
+        }
+        OutputFormat::Html => {
+            tracing::debug!("Showing results as HTML");
+            print!("{}", licensebat_cli::output::to_html(&dependencies));
+        }
         OutputFormat::Markdown => {
-            show_result_as_markdown(&mut dependencies, invalid_dependencies_count);
+            show_result_as_markdown(
+                &mut dependencies,
+                &graph,
+                invalid_dependencies_count,
+                warned_dependencies_count,
+            );
         }
     };
 
-    // exit with 1 if there are invalid dependencies and the behavior is set to block PRs
+    // exit with 1 if there are denied dependencies and the behavior is set to block PRs.
+    // warnings never block
     if invalid_dependencies_count > 0 && !licrc.behavior.do_not_block_pr {
         std::process::exit(1);
     }
@@ -51,18 +120,23 @@ fn show_result_as_json(deps: &[RetrievedDependency]) -> anyhow::Result<()> {
 }
 
 /// Prints the dependencies in the stdout as markdown
-fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencies_count: usize) {
+fn show_result_as_markdown(
+    deps: &mut [RetrievedDependency],
+    graph: &DependencyGraph,
+    invalid_dependencies_count: usize,
+    warned_dependencies_count: usize,
+) {
     tracing::debug!("Showing results as MARKDOWN");
     let total = deps.len();
 
     let md = {
         let header =
-            "| Result | Name |  Version | Type | Validity | Ignored | Licenses | Error | Comments | Is Dev | Is Optional |";
-        let header_separator = "|---|---|---|---|---|---|---|---|---|---|---|";
+            "| Result | Name |  Version | Type | Validity | Ignored | Licenses | Error | Comments | Is Dev | Is Optional | Is Direct | Policy | Introduced By |";
+        let header_separator = "|---|---|---|---|---|---|---|---|---|---|---|---|---|---|";
 
         deps.sort_by(|d1, d2| {
             let o_name = d1.name.cmp(&d2.name);
-            let o_valid = d1.is_valid.cmp(&d2.is_valid);
+            let o_valid = d1.verdict.cmp(&d2.verdict);
 
             match o_valid {
                 std::cmp::Ordering::Equal => o_name,
@@ -70,12 +144,17 @@ fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencie
             }
         });
 
-        let deps_str: Vec<String> = deps
-            .iter()
-            .map(|dep| {
-                format!(
-                    "| {} | **{}** | {} | {} | {} | {} | {} | {} | {} | {} | {} |",
-                    if dep.is_valid {
+        let deps_str: Vec<String> =
+            deps.iter()
+                .map(|dep| {
+                    format!(
+                    "| {} | **{}** | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} | {} |",
+                    // ignored dependencies are not counted as invalid or warned
+                    if dep.is_ignored && dep.verdict != Verdict::Allow {
+                        ":large_blue_circle:"
+                    } else if dep.verdict == Verdict::Warn {
+                        ":orange_circle:"
+                    } else if dep.is_valid {
                         dep.comment.as_ref().map_or(":green_circle:", |c| {
                             if c.remove_when_valid {
                                 ":green_circle:"
@@ -83,8 +162,6 @@ fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencie
                                 ":yellow_circle:"
                             }
                         })
-                    } else if dep.is_ignored {
-                        ":large_blue_circle:"
                     } else {
                         ":red_circle:"
                     },
@@ -94,13 +171,19 @@ fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencie
                     ),
                     dep.version,
                     dep.dependency_type,
-                    if dep.is_valid { "" } else { "Invalid" },
+                    match dep.verdict {
+                        Verdict::Deny => "Invalid",
+                        Verdict::Warn => "Warning",
+                        Verdict::Allow => "",
+                    },
                     if dep.is_ignored {
                         "Ignored by .licrc"
+                    } else if dep.is_exception_expired {
+                        "Exception expired"
                     } else {
                         ""
                     },
-                    dep.licenses.as_ref().unwrap_or(&vec![]).join(", "),
+                    dep.licenses.as_ref().map(ToString::to_string).unwrap_or_default(),
                     dep.error.as_ref().map_or("", |s| s.as_str()),
                     dep.comment.as_ref().map_or("", |c| {
                         if c.remove_when_valid && (dep.is_valid || dep.is_ignored) {
@@ -111,15 +194,20 @@ fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencie
                     }),
                     dep.is_dev.map_or("_", |b| if b { "True" } else { "False" }),
                     dep.is_optional
-                        .map_or("-", |b| if b { "True" } else { "False" })
+                        .map_or("-", |b| if b { "True" } else { "False" }),
+                    dep.is_direct
+                        .map_or("-", |b| if b { "True" } else { "False" }),
+                    dep.policy.map_or_else(String::new, |p| p.to_string()),
+                    introduced_by(graph, dep)
                 )
-            })
-            .collect();
+                })
+                .collect();
 
         format!(
-            "# Licensebat analysis result ({} dependencies - {} invalid)\n{}\n{}\n{}",
+            "# Licensebat analysis result ({} dependencies - {} invalid - {} warnings)\n{}\n{}\n{}",
             total,
             invalid_dependencies_count,
+            warned_dependencies_count,
             header,
             header_separator,
             deps_str.join("\n")
@@ -129,6 +217,25 @@ fn show_result_as_markdown(deps: &mut [RetrievedDependency], invalid_dependencie
     println!("{md}");
 }
 
+/// Gets the direct dependencies that introduced a transitive dependency which is not allowed.
+fn introduced_by(graph: &DependencyGraph, dep: &RetrievedDependency) -> String {
+    if dep.verdict == Verdict::Allow || dep.is_ignored {
+        return String::new();
+    }
+    graph
+        .find(&dep.name, &dep.version)
+        .map(|id| {
+            graph
+                .introduced_by(id)
+                .into_iter()
+                .filter_map(|id| graph.node(id))
+                .map(|node| format!("{}@{}", node.name, node.version))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
+
 /// Sets up the tracing subscriber.
 /// It will use a pretty print in debug and json in --release mode.
 fn set_up_tracing() {
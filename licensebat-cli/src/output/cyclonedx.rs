@@ -0,0 +1,369 @@
+//! [CycloneDX 1.5](https://cyclonedx.org/docs/1.5/json/) SBOM output, in JSON and XML.
+use super::escape_xml;
+use licensebat_core::{license::Expression, PolicyScope, RetrievedDependency};
+use serde::Serialize;
+use std::collections::HashSet;
+
+const SPEC_VERSION: &str = "1.5";
+const XML_NAMESPACE: &str = "http://cyclonedx.org/schema/bom/1.5";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Bom {
+    bom_format: &'static str,
+    spec_version: &'static str,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+}
+
+#[derive(Serialize)]
+struct Metadata {
+    tools: Tools,
+}
+
+#[derive(Serialize)]
+struct Tools {
+    components: Vec<ToolComponent>,
+}
+
+#[derive(Serialize)]
+struct ToolComponent {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    name: &'static str,
+    version: &'static str,
+}
+
+impl Default for Metadata {
+    fn default() -> Self {
+        Self {
+            tools: Tools {
+                components: vec![ToolComponent {
+                    ty: "application",
+                    name: "licensebat",
+                    version: env!("CARGO_PKG_VERSION"),
+                }],
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Component {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    #[serde(rename = "bom-ref")]
+    bom_ref: String,
+    name: String,
+    version: String,
+    scope: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<License>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_references: Vec<ExternalReference>,
+    properties: Vec<Property>,
+}
+
+/// A license choice of a component: either a whole SPDX expression or a license name.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+enum License {
+    Expression(String),
+    License { name: String },
+}
+
+#[derive(Serialize)]
+struct ExternalReference {
+    #[serde(rename = "type")]
+    ty: &'static str,
+    url: String,
+}
+
+#[derive(Serialize)]
+struct Property {
+    name: &'static str,
+    value: String,
+}
+
+impl From<&RetrievedDependency> for Component {
+    fn from(dep: &RetrievedDependency) -> Self {
+        let bom_ref = dep
+            .purl
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}@{}", dep.dependency_type, dep.name, dep.version));
+        // dev dependencies are not part of the runtime
+        let scope = if dep.is_dev.unwrap_or_default() {
+            "excluded"
+        } else if dep.is_optional.unwrap_or_default() {
+            "optional"
+        } else {
+            "required"
+        };
+
+        let mut properties = vec![
+            Property {
+                name: "licensebat:dependency_type",
+                value: dep.dependency_type.clone(),
+            },
+            Property {
+                name: "licensebat:verdict",
+                value: dep.verdict.to_string(),
+            },
+            Property {
+                name: "licensebat:ignored",
+                value: dep.is_ignored.to_string(),
+            },
+        ];
+        if let Some(policy) = dep.policy.filter(|p| *p != PolicyScope::Default) {
+            properties.push(Property {
+                name: "licensebat:policy",
+                value: policy.to_string(),
+            });
+        }
+        if let Some(error) = &dep.error {
+            properties.push(Property {
+                name: "licensebat:error",
+                value: error.clone(),
+            });
+        }
+
+        Self {
+            ty: "library",
+            bom_ref,
+            name: dep.name.clone(),
+            version: dep.version.clone(),
+            scope,
+            licenses: licenses(dep),
+            purl: dep.purl.clone(),
+            external_references: dep
+                .url
+                .iter()
+                .map(|url| ExternalReference {
+                    ty: "website",
+                    url: url.clone(),
+                })
+                .collect(),
+            properties,
+        }
+    }
+}
+
+/// Uses the whole license expression when it's a valid SPDX expression, otherwise every declaration is a license name.
+fn licenses(dep: &RetrievedDependency) -> Vec<License> {
+    let Some(licenses) = &dep.licenses else {
+        return vec![];
+    };
+    let expression = licenses.to_string();
+    if Expression::parse(&expression).is_spdx() {
+        vec![License::Expression(expression)]
+    } else {
+        licenses
+            .licenses()
+            .into_iter()
+            .map(|name| License::License {
+                name: name.to_owned(),
+            })
+            .collect()
+    }
+}
+
+fn bom(dependencies: &[RetrievedDependency]) -> Bom {
+    // the same package may be found several times (i.e. from different sources), but bom-refs must be unique
+    let mut used = HashSet::new();
+    let components = dependencies
+        .iter()
+        .map(|dep| {
+            let mut component = Component::from(dep);
+            let mut count = 1;
+            let mut unique = component.bom_ref.clone();
+            while !used.insert(unique.clone()) {
+                count += 1;
+                unique = format!("{}-{count}", component.bom_ref);
+            }
+            component.bom_ref = unique;
+            component
+        })
+        .collect();
+
+    Bom {
+        bom_format: "CycloneDX",
+        spec_version: SPEC_VERSION,
+        version: 1,
+        metadata: Metadata::default(),
+        components,
+    }
+}
+
+/// Builds a CycloneDX 1.5 SBOM in JSON with a component for every dependency.
+///
+/// Licenses are SPDX expressions when possible and the result of the validation is kept in `licensebat:*` properties.
+///
+/// # Errors
+///
+/// It only fails if the SBOM can't be serialized.
+pub fn to_cyclonedx_json(dependencies: &[RetrievedDependency]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&bom(dependencies))
+}
+
+/// Builds the same SBOM as [`to_cyclonedx_json`] in XML.
+#[must_use]
+pub fn to_cyclonedx_xml(dependencies: &[RetrievedDependency]) -> String {
+    let bom = bom(dependencies);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    push_line!(
+        xml,
+        "<bom xmlns=\"{XML_NAMESPACE}\" version=\"{}\">",
+        bom.version
+    );
+    xml.push_str("  <metadata>\n    <tools>\n      <components>\n");
+    for tool in &bom.metadata.tools.components {
+        push_line!(
+            xml,
+            "        <component type=\"{}\">\n          <name>{}</name>\n          <version>{}</version>\n        </component>",
+            tool.ty, tool.name, tool.version
+        );
+    }
+    xml.push_str("      </components>\n    </tools>\n  </metadata>\n  <components>\n");
+    for component in &bom.components {
+        write_component(&mut xml, component);
+    }
+    xml.push_str("  </components>\n</bom>\n");
+    xml
+}
+
+fn write_component(xml: &mut String, component: &Component) {
+    push_line!(
+        xml,
+        "    <component type=\"{}\" bom-ref=\"{}\">",
+        component.ty,
+        escape_xml(&component.bom_ref)
+    );
+    push_line!(xml, "      <name>{}</name>", escape_xml(&component.name));
+    push_line!(
+        xml,
+        "      <version>{}</version>",
+        escape_xml(&component.version)
+    );
+    push_line!(xml, "      <scope>{}</scope>", component.scope);
+    if !component.licenses.is_empty() {
+        xml.push_str("      <licenses>\n");
+        for license in &component.licenses {
+            match license {
+                License::Expression(expression) => push_line!(
+                    xml,
+                    "        <expression>{}</expression>",
+                    escape_xml(expression)
+                ),
+                License::License { name } => push_line!(
+                    xml,
+                    "        <license>\n          <name>{}</name>\n        </license>",
+                    escape_xml(name)
+                ),
+            }
+        }
+        xml.push_str("      </licenses>\n");
+    }
+    if let Some(purl) = &component.purl {
+        push_line!(xml, "      <purl>{}</purl>", escape_xml(purl));
+    }
+    if !component.external_references.is_empty() {
+        xml.push_str("      <externalReferences>\n");
+        for reference in &component.external_references {
+            push_line!(
+                xml,
+                "        <reference type=\"{}\">\n          <url>{}</url>\n        </reference>",
+                reference.ty,
+                escape_xml(&reference.url)
+            );
+        }
+        xml.push_str("      </externalReferences>\n");
+    }
+    xml.push_str("      <properties>\n");
+    for property in &component.properties {
+        push_line!(
+            xml,
+            "        <property name=\"{}\">{}</property>",
+            property.name,
+            escape_xml(&property.value)
+        );
+    }
+    xml.push_str("      </properties>\n    </component>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::dependency;
+    use licensebat_core::{license::LicenseExpression, Verdict};
+
+    fn dependencies() -> Vec<RetrievedDependency> {
+        let mut other = dependency("a&b", "MIT", Verdict::Deny);
+        other.licenses = LicenseExpression::any_of(["Apache 2.0", "Custom <License>"]);
+        other.purl = None;
+        other.is_dev = Some(true);
+        other.error = Some("Not compliant".to_owned());
+        vec![
+            dependency("serde", "MIT OR Apache-2.0", Verdict::Allow),
+            other,
+        ]
+    }
+
+    #[test]
+    fn components_keep_licenses_scope_and_verdict() {
+        let bom: serde_json::Value =
+            serde_json::from_str(&to_cyclonedx_json(&dependencies()).unwrap()).unwrap();
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], "1.5");
+
+        let serde = &bom["components"][0];
+        assert_eq!(serde["bom-ref"], "pkg:npm/serde@1.0.0");
+        assert_eq!(serde["purl"], "pkg:npm/serde@1.0.0");
+        assert_eq!(serde["scope"], "required");
+        assert_eq!(
+            serde["licenses"],
+            serde_json::json!([{ "expression": "MIT OR Apache-2.0" }])
+        );
+        assert_eq!(serde["properties"][1]["name"], "licensebat:verdict");
+        assert_eq!(serde["properties"][1]["value"], "allow");
+
+        let other = &bom["components"][1];
+        assert_eq!(other["bom-ref"], "npm:a&b@1.0.0");
+        assert_eq!(other["scope"], "excluded");
+        assert_eq!(
+            other["licenses"],
+            serde_json::json!([
+                { "license": { "name": "Apache 2.0" } },
+                { "license": { "name": "Custom <License>" } }
+            ])
+        );
+        assert_eq!(other["properties"][1]["value"], "deny");
+    }
+
+    #[test]
+    fn bom_refs_are_unique() {
+        let dependencies = [
+            dependency("serde", "MIT", Verdict::Allow),
+            dependency("serde", "MIT", Verdict::Allow),
+        ];
+        let bom: serde_json::Value =
+            serde_json::from_str(&to_cyclonedx_json(&dependencies).unwrap()).unwrap();
+        assert_eq!(bom["components"][0]["bom-ref"], "pkg:npm/serde@1.0.0");
+        assert_eq!(bom["components"][1]["bom-ref"], "pkg:npm/serde@1.0.0-2");
+        assert_eq!(bom["components"][1]["purl"], "pkg:npm/serde@1.0.0");
+    }
+
+    #[test]
+    fn xml_is_escaped() {
+        let xml = to_cyclonedx_xml(&dependencies());
+        assert!(xml.contains("<bom xmlns=\"http://cyclonedx.org/schema/bom/1.5\" version=\"1\">"));
+        assert!(xml.contains("<expression>MIT OR Apache-2.0</expression>"));
+        assert!(xml.contains("<name>a&amp;b</name>"));
+        assert!(xml.contains("<name>Custom &lt;License&gt;</name>"));
+        assert!(xml.contains("<property name=\"licensebat:verdict\">deny</property>"));
+        assert!(xml.contains("<scope>excluded</scope>"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Self-contained HTML report, meant to be read by people (i.e. legal reviewers).
+//!
+//! The CSS and the JavaScript needed to filter and sort the table are embedded, so the report is a single static file.
+use super::escape_xml as escape;
+use licensebat_core::{
+    license::{Expression, Term},
+    RetrievedDependency,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+const STYLE: &str = r"
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Helvetica, Arial, sans-serif; margin: 2rem; color: #24292f; }
+h1 { font-size: 1.6rem; }
+.summary { display: flex; gap: 1rem; margin-bottom: 1.5rem; }
+.summary div { border: 1px solid #d0d7de; border-radius: 6px; padding: .75rem 1.25rem; text-align: center; }
+.summary strong { display: block; font-size: 1.5rem; }
+.filters { display: flex; gap: .5rem; margin-bottom: 1rem; }
+.filters input { flex: 1; }
+.filters input, .filters select { padding: .4rem; border: 1px solid #d0d7de; border-radius: 6px; }
+table { border-collapse: collapse; width: 100%; font-size: .9rem; }
+th, td { border-bottom: 1px solid #d0d7de; padding: .4rem .6rem; text-align: left; vertical-align: top; }
+th { cursor: pointer; user-select: none; background: #f6f8fa; position: sticky; top: 0; }
+th[data-order='asc']::after { content: ' \25B2'; }
+th[data-order='desc']::after { content: ' \25BC'; }
+.deny { color: #cf222e; font-weight: bold; }
+.warn { color: #9a6700; font-weight: bold; }
+.allow { color: #1a7f37; }
+.ignored { color: #0969da; }
+details { margin-bottom: .4rem; }
+";
+
+const SCRIPT: &str = r"
+const table = document.getElementById('dependencies');
+const rows = Array.from(table.tBodies[0].rows);
+const search = document.getElementById('search');
+const verdict = document.getElementById('verdict');
+function filter() {
+  const text = search.value.toLowerCase();
+  rows.forEach((row) => {
+    const matchesText = row.textContent.toLowerCase().includes(text);
+    const matchesVerdict = !verdict.value || row.dataset.verdict === verdict.value;
+    row.hidden = !(matchesText && matchesVerdict);
+  });
+}
+search.addEventListener('input', filter);
+verdict.addEventListener('change', filter);
+table.tHead.querySelectorAll('th').forEach((th, column) => {
+  th.addEventListener('click', () => {
+    const order = th.dataset.order === 'asc' ? 'desc' : 'asc';
+    table.tHead.querySelectorAll('th').forEach((other) => delete other.dataset.order);
+    th.dataset.order = order;
+    const value = (row) => row.cells[column].dataset.sort ?? row.cells[column].textContent.trim();
+    rows.sort((a, b) => value(a).localeCompare(value(b), undefined, { numeric: true }));
+    if (order === 'desc') rows.reverse();
+    rows.forEach((row) => table.tBodies[0].appendChild(row));
+  });
+});
+";
+
+/// Gets the verdict shown in the report. Ignored dependencies have their own one.
+fn verdict(dep: &RetrievedDependency) -> String {
+    if dep.is_ignored {
+        "ignored".to_owned()
+    } else {
+        dep.verdict.to_string()
+    }
+}
+
+/// Gets the link to a dependency, only if it's an http url.
+fn link(dep: &RetrievedDependency) -> String {
+    match &dep.url {
+        Some(url) if url.starts_with("https://") || url.starts_with("http://") => format!(
+            "<a href=\"{}\" target=\"_blank\" rel=\"noopener noreferrer\">{}</a>",
+            escape(url),
+            escape(&dep.name)
+        ),
+        _ => escape(&dep.name),
+    }
+}
+
+fn licenses(dep: &RetrievedDependency) -> String {
+    dep.licenses
+        .as_ref()
+        .map_or_else(|| "No license".to_owned(), ToString::to_string)
+}
+
+fn flag(value: Option<bool>) -> &'static str {
+    value.map_or("-", |b| if b { "yes" } else { "no" })
+}
+
+/// Builds a self-contained HTML report with summary counts, a filterable and sortable table of the dependencies
+/// and the dependencies grouped by license.
+#[must_use]
+pub fn to_html(dependencies: &[RetrievedDependency]) -> String {
+    let count = |value: &str| dependencies.iter().filter(|d| verdict(d) == value).count();
+
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>Licensebat report</title>\n");
+    push_line!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+    html.push_str("<h1>Licensebat report</h1>\n<div class=\"summary\">\n");
+    for (label, value, class) in [
+        ("Dependencies", dependencies.len(), ""),
+        ("Invalid", count("deny"), "deny"),
+        ("Warnings", count("warn"), "warn"),
+        ("Valid", count("allow"), "allow"),
+        ("Ignored", count("ignored"), "ignored"),
+    ] {
+        push_line!(
+            html,
+            "<div class=\"{class}\"><strong>{value}</strong>{label}</div>"
+        );
+    }
+    html.push_str("</div>\n");
+
+    html.push_str("<h2>Dependencies</h2>\n<div class=\"filters\">\n<input id=\"search\" type=\"search\" placeholder=\"Filter by name, license, comment...\">\n<select id=\"verdict\">\n<option value=\"\">All verdicts</option>\n<option value=\"deny\">Invalid</option>\n<option value=\"warn\">Warning</option>\n<option value=\"allow\">Valid</option>\n<option value=\"ignored\">Ignored</option>\n</select>\n</div>\n");
+    html.push_str("<table id=\"dependencies\">\n<thead><tr><th>Name</th><th>Version</th><th>Type</th><th>Licenses</th><th>Verdict</th><th>Dev</th><th>Optional</th><th>Comment</th></tr></thead>\n<tbody>\n");
+    for dep in dependencies {
+        let verdict = verdict(dep);
+        let comment = [
+            dep.error.as_deref(),
+            dep.comment
+                .as_ref()
+                .filter(|c| !(c.remove_when_valid && (dep.is_valid || dep.is_ignored)))
+                .map(|c| c.text.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(". ");
+        push_line!(
+            html,
+            "<tr data-verdict=\"{verdict}\"><td data-sort=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"{verdict}\">{verdict}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&dep.name),
+            link(dep),
+            escape(&dep.version),
+            escape(&dep.dependency_type),
+            escape(&licenses(dep)),
+            flag(dep.is_dev),
+            flag(dep.is_optional),
+            escape(&comment),
+        );
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    // dependencies declaring several licenses are listed under each one of them,
+    // no matter if they are declared as a list or as a single SPDX expression
+    let mut by_license = BTreeMap::<String, Vec<&RetrievedDependency>>::new();
+    for dep in dependencies {
+        let licenses = dep.licenses.as_ref().map_or_else(
+            || BTreeSet::from(["No license".to_owned()]),
+            |licenses| {
+                licenses
+                    .licenses()
+                    .into_iter()
+                    .flat_map(|license| {
+                        Expression::parse(license)
+                            .terms()
+                            .iter()
+                            .map(Term::canonical)
+                            .collect::<Vec<_>>()
+                    })
+                    .collect()
+            },
+        );
+        for license in licenses {
+            by_license.entry(license).or_default().push(dep);
+        }
+    }
+    html.push_str("<h2>Licenses</h2>\n");
+    for (license, deps) in by_license {
+        push_line!(
+            html,
+            "<details>\n<summary>{} ({})</summary>\n<ul>",
+            escape(&license),
+            deps.len()
+        );
+        for dep in deps {
+            let verdict = verdict(dep);
+            push_line!(
+                html,
+                "<li>{} {} <span class=\"{verdict}\">{verdict}</span></li>",
+                link(dep),
+                escape(&dep.version),
+            );
+        }
+        html.push_str("</ul>\n</details>\n");
+    }
+
+    push_line!(html, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::dependency;
+    use licensebat_core::{license::LicenseExpression, Verdict};
+
+    #[test]
+    fn it_renders_summary_table_and_license_groups() {
+        let mut ignored = dependency("ignored", "GPL-3.0-only", Verdict::Deny);
+        ignored.is_ignored = true;
+        let mut unsafe_url = dependency("<script>", "MIT", Verdict::Allow);
+        unsafe_url.url = Some("javascript:alert(1)".to_owned());
+        let mut dual = dependency("dual", "MIT", Verdict::Allow);
+        dual.licenses = LicenseExpression::any_of(["MIT", "ISC"]);
+        let html = to_html(&[
+            dependency("left-pad", "MIT", Verdict::Allow),
+            dual,
+            dependency("spdx-dual", "MIT OR ISC", Verdict::Allow),
+            dependency("gpl", "GPL-3.0-only", Verdict::Deny),
+            ignored,
+            unsafe_url,
+        ]);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<div class=\"deny\"><strong>1</strong>Invalid</div>"));
+        assert!(html.contains("<div class=\"ignored\"><strong>1</strong>Ignored</div>"));
+        assert!(html.contains(
+            "<a href=\"https://www.npmjs.com/package/gpl/v/1.0.0\" target=\"_blank\" rel=\"noopener noreferrer\">gpl</a>"
+        ));
+        assert!(html.contains("<tr data-verdict=\"deny\">"));
+        assert!(html.contains("<summary>MIT (4)</summary>"));
+        assert!(html.contains("<summary>GPL-3.0-only (2)</summary>"));
+        assert!(!html.contains("<summary>MIT OR ISC"));
+        assert!(html.contains("<summary>ISC (2)</summary>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("javascript:alert"));
+    }
+}
@@ -0,0 +1,177 @@
+//! JUnit XML output, rendered natively by most CI servers (Jenkins, GitLab, Azure DevOps...).
+use super::escape_xml;
+use licensebat_core::{FailureReason, RetrievedDependency, Verdict};
+use std::collections::BTreeMap;
+
+/// Outcome of the test case of a dependency.
+enum Outcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl From<&RetrievedDependency> for Outcome {
+    fn from(dep: &RetrievedDependency) -> Self {
+        if dep.is_ignored {
+            Self::Skipped
+        } else if dep.verdict == Verdict::Deny {
+            Self::Failed
+        } else {
+            Self::Passed
+        }
+    }
+}
+
+/// Counts of a test suite.
+#[derive(Default)]
+struct Counts {
+    tests: usize,
+    failures: usize,
+    skipped: usize,
+}
+
+impl Counts {
+    fn add(&mut self, outcome: &Outcome) {
+        self.tests += 1;
+        match outcome {
+            Outcome::Failed => self.failures += 1,
+            Outcome::Skipped => self.skipped += 1,
+            Outcome::Passed => (),
+        }
+    }
+}
+
+/// Builds a JUnit XML report with a test suite per dependency type and a test case per dependency.
+///
+/// Invalid dependencies are failures carrying their error and comment, ignored ones are skipped
+/// and warnings are reported in the output of the test case without failing it.
+#[must_use]
+pub fn to_junit(dependencies: &[RetrievedDependency]) -> String {
+    let mut suites = BTreeMap::<&str, Vec<&RetrievedDependency>>::new();
+    for dep in dependencies {
+        suites.entry(&dep.dependency_type).or_default().push(dep);
+    }
+
+    let mut total = Counts::default();
+    let mut body = String::new();
+    for (dependency_type, deps) in suites {
+        let mut counts = Counts::default();
+        let mut cases = String::new();
+        for dep in deps {
+            let outcome = Outcome::from(dep);
+            counts.add(&outcome);
+            total.add(&outcome);
+            write_test_case(&mut cases, dep, &outcome);
+        }
+        push_line!(
+            body,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">",
+            escape_xml(dependency_type),
+            counts.tests,
+            counts.failures,
+            counts.skipped
+        );
+        body.push_str(&cases);
+        body.push_str("  </testsuite>\n");
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"licensebat\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">\n{body}</testsuites>\n",
+        total.tests, total.failures, total.skipped
+    )
+}
+
+fn write_test_case(xml: &mut String, dep: &RetrievedDependency, outcome: &Outcome) {
+    let licenses = dep
+        .licenses
+        .as_ref()
+        .map_or_else(|| "none".to_owned(), ToString::to_string);
+    let comment = dep
+        .comment
+        .as_ref()
+        .filter(|c| !(c.remove_when_valid && (dep.is_valid || dep.is_ignored)))
+        .map(|c| c.text.as_str());
+
+    push_line!(
+        xml,
+        "    <testcase classname=\"licensebat.{}\" name=\"{}@{}\">",
+        escape_xml(&dep.dependency_type),
+        escape_xml(&dep.name),
+        escape_xml(&dep.version)
+    );
+    match outcome {
+        Outcome::Failed => {
+            let error = dep.error.as_deref().unwrap_or("Not compliant");
+            let mut text = format!("{error}\nLicenses: {licenses}");
+            if let Some(comment) = comment {
+                text.push('\n');
+                text.push_str(comment);
+            }
+            push_line!(
+                xml,
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                escape_xml(error),
+                dep.failure_reason.unwrap_or(FailureReason::NotCompliant),
+                escape_xml(&text)
+            );
+        }
+        Outcome::Skipped => {
+            let message = comment.unwrap_or("Ignored by .licrc");
+            push_line!(xml, "      <skipped message=\"{}\"/>", escape_xml(message));
+        }
+        Outcome::Passed => {
+            let mut text = format!("Licenses: {licenses}");
+            if dep.verdict == Verdict::Warn {
+                text.push_str("\nWarning");
+            }
+            if let Some(comment) = comment {
+                text.push('\n');
+                text.push_str(comment);
+            }
+            push_line!(xml, "      <system-out>{}</system-out>", escape_xml(&text));
+        }
+    }
+    xml.push_str("    </testcase>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::dependency;
+    use licensebat_core::Comment;
+
+    #[test]
+    fn invalid_dependencies_fail_and_ignored_ones_are_skipped() {
+        let mut invalid = dependency("gpl", "GPL-3.0-only", Verdict::Deny);
+        invalid.error = Some("Not compliant".to_owned());
+        invalid.failure_reason = Some(FailureReason::NotCompliant);
+        invalid.comment = Some(Comment::non_removable("Ask <legal> & wait"));
+        let mut ignored = dependency("ignored", "MIT", Verdict::Deny);
+        ignored.is_ignored = true;
+        let mut rust = dependency("serde", "MIT", Verdict::Warn);
+        rust.dependency_type = "rust".to_owned();
+
+        let xml = to_junit(&[
+            dependency("ok", "MIT", Verdict::Allow),
+            invalid,
+            ignored,
+            rust,
+        ]);
+
+        assert!(xml.contains(
+            "<testsuites name=\"licensebat\" tests=\"4\" failures=\"1\" errors=\"0\" skipped=\"1\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"npm\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"rust\" tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"0\">"
+        ));
+        assert!(xml.contains("<testcase classname=\"licensebat.npm\" name=\"gpl@1.0.0\">"));
+        assert!(xml.contains(
+            "<failure message=\"Not compliant\" type=\"not_compliant\">Not compliant\nLicenses: GPL-3.0-only\nAsk &lt;legal&gt; &amp; wait</failure>"
+        ));
+        assert!(xml.contains("<skipped message=\"Ignored by .licrc\"/>"));
+        assert!(xml.contains("<system-out>Licenses: MIT\nWarning</system-out>"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Formats the result of the check so it can be consumed by other tools.
+
+/// Appends a formatted line to a document being built, like `writeln!` does.
+///
+/// Writing to a `String` never fails, so there's no `Result` to deal with.
+macro_rules! push_line {
+    ($document:expr, $($arg:tt)*) => {{
+        use std::fmt::Write as _;
+        let _ = writeln!($document, $($arg)*);
+    }};
+}
+
+mod cyclonedx;
+mod html;
+mod junit;
+mod sarif;
+mod spdx;
+
+pub use cyclonedx::{to_cyclonedx_json, to_cyclonedx_xml};
+pub use html::to_html;
+pub use junit::to_junit;
+pub use sarif::to_sarif;
+pub use spdx::{to_spdx_json, to_spdx_tag_value};
+
+/// Escapes the characters that can't be used as is in XML text and attributes.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds a retrieved npm dependency with a single license, shared by the tests of every format.
+#[cfg(test)]
+fn dependency(
+    name: &str,
+    license: &str,
+    verdict: licensebat_core::Verdict,
+) -> licensebat_core::RetrievedDependency {
+    licensebat_core::RetrievedDependency {
+        name: name.to_owned(),
+        version: "1.0.0".to_owned(),
+        dependency_type: "npm".to_owned(),
+        url: Some(format!("https://www.npmjs.com/package/{name}/v/1.0.0")),
+        purl: Some(format!("pkg:npm/{name}@1.0.0")),
+        licenses: Some(licensebat_core::license::LicenseExpression::license(
+            license,
+        )),
+        ..licensebat_core::RetrievedDependency::default()
+    }
+    .with_verdict(verdict)
+}
@@ -0,0 +1,352 @@
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) output,
+//! which can be uploaded to GitHub code scanning and most security platforms.
+use licensebat_core::{FailureReason, RetrievedDependency, Verdict};
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path, PathBuf},
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const VERSION: &str = "2.1.0";
+
+#[derive(Serialize)]
+struct Log<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+struct Run<'a> {
+    tool: Tool,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    short_description: Message,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    partial_fingerprints: Fingerprints,
+    properties: Properties<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Fingerprints {
+    #[serde(rename = "licensebat/v1")]
+    dependency: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Properties<'a> {
+    dependency_type: &'a str,
+    name: &'a str,
+    version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    licenses: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    purl: Option<&'a str>,
+}
+
+/// Gets the id, name and description of the rule of a [`FailureReason`].
+const fn rule(reason: FailureReason) -> (&'static str, &'static str, &'static str) {
+    match reason {
+        FailureReason::NetworkError => (
+            "network_error",
+            "NetworkError",
+            "The source of the dependency couldn't be reached.",
+        ),
+        FailureReason::NotFound => (
+            "not_found",
+            "NotFound",
+            "The dependency (or its version) doesn't exist in its source.",
+        ),
+        FailureReason::NoLicenseDeclared => (
+            "no_license_declared",
+            "NoLicenseDeclared",
+            "The dependency doesn't declare any license.",
+        ),
+        FailureReason::NotCompliant => (
+            "not_compliant",
+            "NotCompliant",
+            "The licenses of the dependency are not compliant with the .licrc file.",
+        ),
+        FailureReason::UnsupportedSource => (
+            "unsupported_source",
+            "UnsupportedSource",
+            "The source of the dependency is not supported.",
+        ),
+        FailureReason::ParseError => (
+            "parse_error",
+            "ParseError",
+            "The information about the dependency couldn't be parsed.",
+        ),
+        FailureReason::Other => (
+            "other",
+            "Other",
+            "The dependency couldn't be retrieved or validated.",
+        ),
+    }
+}
+
+/// Points at the dependency file relative to `%SRCROOT%`, the directory licensebat runs from.
+///
+/// Files out of it can't be expressed relative to `%SRCROOT%`, so they get an absolute `file` URI without a base.
+fn artifact_location(dependency_file: &str, current_dir: &Path) -> ArtifactLocation {
+    let path = normalize(&current_dir.join(dependency_file));
+    match path.strip_prefix(normalize(current_dir)) {
+        Ok(relative) => ArtifactLocation {
+            uri: relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            uri_base_id: Some("%SRCROOT%"),
+        },
+        Err(_) => {
+            let path = path.to_string_lossy().replace('\\', "/");
+            let separator = if path.starts_with('/') { "" } else { "/" };
+            ArtifactLocation {
+                uri: format!("file://{separator}{path}"),
+                uri_base_id: None,
+            }
+        }
+    }
+}
+
+/// Resolves `.` and `..` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Builds a SARIF 2.1.0 log with a result for every invalid dependency that is not ignored.
+///
+/// Each [`FailureReason`] is a rule and every result points at the dependency file.
+///
+/// # Errors
+///
+/// It only fails if the log can't be serialized.
+pub fn to_sarif(
+    dependencies: &[RetrievedDependency],
+    dependency_file: &str,
+) -> serde_json::Result<String> {
+    let artifact_location = artifact_location(
+        dependency_file,
+        &std::env::current_dir().unwrap_or_default(),
+    );
+    let invalid = dependencies
+        .iter()
+        .filter(|dep| dep.verdict == Verdict::Deny && !dep.is_ignored)
+        .map(|dep| (dep, dep.failure_reason.unwrap_or(FailureReason::Other)))
+        .collect::<Vec<_>>();
+
+    let reasons = invalid
+        .iter()
+        .map(|(_, reason)| *reason)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let results = invalid
+        .into_iter()
+        .map(|(dep, reason)| {
+            let (rule_id, ..) = rule(reason);
+            let licenses = dep.licenses.as_ref().map(ToString::to_string);
+            let text = format!(
+                "{} {}@{}: {} (licenses: {})",
+                dep.dependency_type,
+                dep.name,
+                dep.version,
+                dep.error.as_deref().unwrap_or("Not compliant"),
+                licenses.as_deref().unwrap_or("none"),
+            );
+            SarifResult {
+                rule_id,
+                rule_index: reasons.binary_search(&reason).unwrap_or_default(),
+                level: "error",
+                message: Message { text },
+                locations: vec![Location {
+                    physical_location: PhysicalLocation {
+                        artifact_location: artifact_location.clone(),
+                    },
+                }],
+                partial_fingerprints: Fingerprints {
+                    dependency: format!("{}/{}@{}", dep.dependency_type, dep.name, dep.version),
+                },
+                properties: Properties {
+                    dependency_type: &dep.dependency_type,
+                    name: &dep.name,
+                    version: &dep.version,
+                    licenses,
+                    purl: dep.purl.as_deref(),
+                },
+            }
+        })
+        .collect();
+
+    let rules = reasons
+        .into_iter()
+        .map(|reason| {
+            let (id, name, description) = rule(reason);
+            Rule {
+                id,
+                name,
+                short_description: Message {
+                    text: description.to_owned(),
+                },
+            }
+        })
+        .collect();
+
+    let log = Log {
+        schema: SCHEMA,
+        version: VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "licensebat",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://licensebat.com",
+                    rules,
+                },
+            },
+            results,
+        }],
+    };
+    serde_json::to_string_pretty(&log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::dependency;
+
+    fn invalid(name: &str, reason: FailureReason) -> RetrievedDependency {
+        RetrievedDependency {
+            failure_reason: Some(reason),
+            ..dependency(name, "GPL-3.0-only", Verdict::Deny)
+        }
+    }
+
+    #[test]
+    fn invalid_dependencies_are_results_of_a_rule_per_failure_reason() {
+        let mut ignored = invalid("ignored", FailureReason::NotCompliant);
+        ignored.is_ignored = true;
+        let dependencies = vec![
+            dependency("valid", "GPL-3.0-only", Verdict::Allow),
+            dependency("warned", "GPL-3.0-only", Verdict::Warn),
+            ignored,
+            invalid("gpl", FailureReason::NotCompliant),
+            invalid("missing", FailureReason::NotFound),
+            invalid("other-gpl", FailureReason::NotCompliant),
+        ];
+
+        let sarif: serde_json::Value =
+            serde_json::from_str(&to_sarif(&dependencies, "./package-lock.json").unwrap()).unwrap();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "not_found");
+        assert_eq!(rules[1]["id"], "not_compliant");
+
+        let results = run["results"].as_array().unwrap();
+        let names = results
+            .iter()
+            .map(|r| r["properties"]["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["gpl", "missing", "other-gpl"]);
+        assert_eq!(results[0]["ruleId"], "not_compliant");
+        assert_eq!(results[0]["ruleIndex"], 1);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "package-lock.json"
+        );
+        assert_eq!(results[0]["properties"]["licenses"], "GPL-3.0-only");
+    }
+
+    #[test]
+    fn dependency_files_are_relative_to_the_source_root() {
+        let root = Path::new("/project");
+        let location = artifact_location("./package-lock.json", root);
+        assert_eq!(location.uri, "package-lock.json");
+        assert_eq!(location.uri_base_id, Some("%SRCROOT%"));
+
+        let location = artifact_location("/project/app/Cargo.lock", root);
+        assert_eq!(location.uri, "app/Cargo.lock");
+        assert_eq!(location.uri_base_id, Some("%SRCROOT%"));
+
+        let location = artifact_location("/other/yarn.lock", root);
+        assert_eq!(location.uri, "file:///other/yarn.lock");
+        assert_eq!(location.uri_base_id, None);
+
+        let location = artifact_location("../other/pubspec.lock", root);
+        assert_eq!(location.uri, "file:///other/pubspec.lock");
+        assert_eq!(location.uri_base_id, None);
+    }
+}
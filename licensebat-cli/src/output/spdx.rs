@@ -0,0 +1,581 @@
+//! [SPDX 2.3](https://spdx.github.io/spdx-spec/v2.3/) document output, in JSON and tag-value formats.
+use licensebat_core::{
+    license::Expression, DependencyGraph, Detector, FailureReason, LicenseSource,
+    RetrievedDependency,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const SPDX_VERSION: &str = "SPDX-2.3";
+const DATA_LICENSE: &str = "CC0-1.0";
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+const NOASSERTION: &str = "NOASSERTION";
+const NONE: &str = "NONE";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Document {
+    spdx_version: &'static str,
+    data_license: &'static str,
+    #[serde(rename = "SPDXID")]
+    spdx_id: &'static str,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<Package>,
+    relationships: Vec<Relationship>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    has_extracted_licensing_infos: Vec<ExtractedLicense>,
+}
+
+/// When and by whom the document has been created.
+#[derive(Serialize)]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+impl CreationInfo {
+    fn now() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::at(seconds)
+    }
+
+    /// Creation info for the given number of seconds since 1970-01-01.
+    fn at(seconds: u64) -> Self {
+        let days = i64::try_from(seconds / 86_400).unwrap_or(i64::MAX);
+        let time = seconds % 86_400;
+        Self {
+            created: format!(
+                "{}T{:02}:{:02}:{:02}Z",
+                civil_date(days),
+                time / 3_600,
+                time % 3_600 / 60,
+                time % 60
+            ),
+            creators: vec![format!("Tool: licensebat-{}", env!("CARGO_PKG_VERSION"))],
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    name: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    version_info: String,
+    download_location: String,
+    files_analyzed: bool,
+    license_concluded: String,
+    license_declared: String,
+    copyright_text: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    external_refs: Vec<ExternalRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExternalRef {
+    reference_category: &'static str,
+    reference_type: &'static str,
+    reference_locator: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: &'static str,
+    related_spdx_element: String,
+}
+
+/// A license that is not in the SPDX list, referenced as `LicenseRef-*`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractedLicense {
+    license_id: String,
+    extracted_text: String,
+    name: String,
+}
+
+/// Converts the licenses of the dependencies to SPDX expressions, keeping track of the licenses that are not in the SPDX list.
+#[derive(Default)]
+struct Licenses {
+    /// Extracted texts by their `LicenseRef` id.
+    extracted: BTreeMap<String, String>,
+    /// `LicenseRef` ids by their extracted text, so different texts never share an id.
+    ids: HashMap<String, String>,
+}
+
+impl Licenses {
+    /// Gets the SPDX expression of the licenses of a dependency, or `NOASSERTION`.
+    fn expression(&mut self, dep: &RetrievedDependency) -> String {
+        let Some(licenses) = &dep.licenses else {
+            return NOASSERTION.to_owned();
+        };
+        let mut licenses = licenses.clone();
+        licenses.for_each_license_mut(&mut |license| {
+            // declarations parsed in lax mode (i.e. with lower case operators) are written in their canonical form
+            if let Some(canonical) = Expression::parse(license).canonical() {
+                *license = canonical;
+            } else {
+                let id = self.ids.entry(license.clone()).or_insert_with(|| {
+                    let id = format!("LicenseRef-{}", sanitize(license));
+                    let mut unique = id.clone();
+                    let mut count = 1;
+                    while self.extracted.contains_key(&unique) {
+                        count += 1;
+                        unique = format!("{id}-{count}");
+                    }
+                    self.extracted.insert(unique.clone(), license.clone());
+                    unique
+                });
+                *license = id.clone();
+            }
+        });
+        licenses.to_string()
+    }
+
+    /// Gets the license declared by the source of a dependency, which may differ from the concluded one
+    /// when it has been overridden or detected by analyzing a license file.
+    fn declared(&mut self, dep: &RetrievedDependency) -> String {
+        if dep.failure_reason == Some(FailureReason::NoLicenseDeclared) {
+            return NONE.to_owned();
+        }
+        let is_declared = dep.provenance.as_ref().is_none_or(|provenance| {
+            provenance.detector == Detector::Declared
+                && provenance.source != LicenseSource::Override
+        });
+        if is_declared {
+            self.expression(dep)
+        } else {
+            NOASSERTION.to_owned()
+        }
+    }
+}
+
+/// Replaces the characters that are not allowed in SPDX identifiers.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Gives a unique SPDX identifier to every package.
+#[derive(Default)]
+struct Ids {
+    used: HashSet<String>,
+}
+
+impl Ids {
+    fn next(&mut self, name: &str, version: &str) -> String {
+        let id = format!("SPDXRef-Package-{}-{}", sanitize(name), sanitize(version));
+        let mut unique = id.clone();
+        let mut count = 1;
+        while !self.used.insert(unique.clone()) {
+            count += 1;
+            unique = format!("{id}-{count}");
+        }
+        unique
+    }
+}
+
+fn document(
+    dependencies: &[RetrievedDependency],
+    graph: &DependencyGraph,
+    name: &str,
+    creation_info: CreationInfo,
+    namespace_suffix: &str,
+) -> Document {
+    let mut ids = Ids::default();
+    let mut licenses = Licenses::default();
+    let mut package_ids = HashMap::new();
+
+    let mut packages = dependencies
+        .iter()
+        .map(|dep| {
+            let spdx_id = ids.next(&dep.name, &dep.version);
+            package_ids.insert((dep.name.as_str(), dep.version.as_str()), spdx_id.clone());
+            Package {
+                name: dep.name.clone(),
+                spdx_id,
+                version_info: dep.version.clone(),
+                download_location: dep.url.clone().unwrap_or_else(|| NOASSERTION.to_owned()),
+                files_analyzed: false,
+                license_concluded: licenses.expression(dep),
+                license_declared: licenses.declared(dep),
+                copyright_text: NOASSERTION,
+                external_refs: dep
+                    .purl
+                    .iter()
+                    .map(|purl| ExternalRef {
+                        reference_category: "PACKAGE-MANAGER",
+                        reference_type: "purl",
+                        reference_locator: purl.clone(),
+                    })
+                    .collect(),
+                comment: Some(format!(
+                    "Licensebat verdict: {}{}",
+                    dep.verdict,
+                    dep.error
+                        .as_ref()
+                        .map(|error| format!(" ({error})"))
+                        .unwrap_or_default()
+                )),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // roots (the project or its workspace members) are not dependencies, but they're described by the document
+    for root in graph.roots().filter_map(|id| graph.node(id)) {
+        let key = (root.name.as_str(), root.version.as_str());
+        if package_ids.contains_key(&key) {
+            continue;
+        }
+        let spdx_id = ids.next(&root.name, &root.version);
+        package_ids.insert(key, spdx_id.clone());
+        packages.push(Package {
+            name: root.name.clone(),
+            spdx_id,
+            version_info: root.version.clone(),
+            download_location: NOASSERTION.to_owned(),
+            files_analyzed: false,
+            license_concluded: NOASSERTION.to_owned(),
+            license_declared: NOASSERTION.to_owned(),
+            copyright_text: NOASSERTION,
+            external_refs: vec![],
+            comment: None,
+        });
+    }
+
+    let package_id = |id| {
+        graph
+            .node(id)
+            .and_then(|node| package_ids.get(&(node.name.as_str(), node.version.as_str())))
+    };
+    let describes = |spdx_id: &String| Relationship {
+        spdx_element_id: DOCUMENT_ID.to_owned(),
+        relationship_type: "DESCRIBES",
+        related_spdx_element: spdx_id.clone(),
+    };
+
+    // without roots, the document describes the direct dependencies (or all of them if that's unknown)
+    let mut relationships = if graph.roots().next().is_some() {
+        graph
+            .roots()
+            .filter_map(package_id)
+            .map(describes)
+            .collect()
+    } else {
+        dependencies
+            .iter()
+            .filter(|dep| dep.is_direct != Some(false))
+            .filter_map(|dep| package_ids.get(&(dep.name.as_str(), dep.version.as_str())))
+            .map(describes)
+            .collect::<Vec<_>>()
+    };
+    relationships.extend(graph.edges().iter().filter_map(|edge| {
+        Some(Relationship {
+            spdx_element_id: package_id(edge.from)?.clone(),
+            relationship_type: "DEPENDS_ON",
+            related_spdx_element: package_id(edge.to)?.clone(),
+        })
+    }));
+
+    Document {
+        spdx_version: SPDX_VERSION,
+        data_license: DATA_LICENSE,
+        spdx_id: DOCUMENT_ID,
+        name: name.to_owned(),
+        document_namespace: format!(
+            "https://licensebat.com/spdxdocs/{}-{namespace_suffix}",
+            sanitize(name)
+        ),
+        creation_info,
+        packages,
+        relationships,
+        has_extracted_licensing_infos: licenses
+            .extracted
+            .into_iter()
+            .map(|(license_id, text)| ExtractedLicense {
+                license_id,
+                extracted_text: text.clone(),
+                name: text,
+            })
+            .collect(),
+    }
+}
+
+/// Builds a new document whose namespace is made unique by its creation time.
+fn new_document(
+    dependencies: &[RetrievedDependency],
+    graph: &DependencyGraph,
+    name: &str,
+) -> Document {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    document(
+        dependencies,
+        graph,
+        name,
+        CreationInfo::now(),
+        &nanos.to_string(),
+    )
+}
+
+/// Builds an SPDX 2.3 document in JSON with a package for every dependency.
+///
+/// The concluded license is the one used to validate the dependency (after overrides and detection)
+/// and the relationships come from the [`DependencyGraph`] when the collector supports it.
+/// Licenses that are not in the SPDX list are referenced as `LicenseRef-*`.
+///
+/// # Errors
+///
+/// It only fails if the document can't be serialized.
+pub fn to_spdx_json(
+    dependencies: &[RetrievedDependency],
+    graph: &DependencyGraph,
+    name: &str,
+) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&new_document(dependencies, graph, name))
+}
+
+/// Builds the same document as [`to_spdx_json`] in tag-value format.
+#[must_use]
+pub fn to_spdx_tag_value(
+    dependencies: &[RetrievedDependency],
+    graph: &DependencyGraph,
+    name: &str,
+) -> String {
+    tag_value(&new_document(dependencies, graph, name))
+}
+
+fn tag_value(document: &Document) -> String {
+    let mut tv = String::new();
+    push_line!(tv, "SPDXVersion: {}", document.spdx_version);
+    push_line!(tv, "DataLicense: {}", document.data_license);
+    push_line!(tv, "SPDXID: {}", document.spdx_id);
+    push_line!(tv, "DocumentName: {}", document.name);
+    push_line!(tv, "DocumentNamespace: {}", document.document_namespace);
+    for creator in &document.creation_info.creators {
+        push_line!(tv, "Creator: {creator}");
+    }
+    push_line!(tv, "Created: {}", document.creation_info.created);
+
+    for package in &document.packages {
+        push_line!(tv, "\n##### Package: {}\n", package.name);
+        push_line!(tv, "PackageName: {}", package.name);
+        push_line!(tv, "SPDXID: {}", package.spdx_id);
+        push_line!(tv, "PackageVersion: {}", package.version_info);
+        push_line!(tv, "PackageDownloadLocation: {}", package.download_location);
+        push_line!(tv, "FilesAnalyzed: {}", package.files_analyzed);
+        push_line!(tv, "PackageLicenseConcluded: {}", package.license_concluded);
+        push_line!(tv, "PackageLicenseDeclared: {}", package.license_declared);
+        push_line!(tv, "PackageCopyrightText: {}", package.copyright_text);
+        for external_ref in &package.external_refs {
+            push_line!(
+                tv,
+                "ExternalRef: {} {} {}",
+                external_ref.reference_category,
+                external_ref.reference_type,
+                external_ref.reference_locator
+            );
+        }
+        if let Some(comment) = &package.comment {
+            push_line!(tv, "PackageComment: <text>{comment}</text>");
+        }
+    }
+
+    if !document.relationships.is_empty() {
+        tv.push_str("\n##### Relationships\n\n");
+    }
+    for relationship in &document.relationships {
+        push_line!(
+            tv,
+            "Relationship: {} {} {}",
+            relationship.spdx_element_id,
+            relationship.relationship_type,
+            relationship.related_spdx_element
+        );
+    }
+
+    for license in &document.has_extracted_licensing_infos {
+        push_line!(tv, "\n##### Extracted license: {}\n", license.license_id);
+        push_line!(tv, "LicenseID: {}", license.license_id);
+        push_line!(tv, "ExtractedText: <text>{}</text>", license.extracted_text);
+        push_line!(tv, "LicenseName: {}", license.name);
+    }
+    tv
+}
+
+/// Converts the number of days since 1970-01-01 to a `YYYY-MM-DD` date.
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::dependency;
+    use licensebat_core::{license::LicenseExpression, Provenance, Verdict};
+
+    fn build() -> Document {
+        let mut graph = DependencyGraph::new();
+        let app = graph.add_node("app", "0.1.0");
+        let a = graph.add_node("a", "1.0.0");
+        let b = graph.add_node("b", "1.0.0");
+        graph.mark_root(app);
+        graph.add_edge(app, a);
+        graph.add_edge(a, b);
+        graph.mark_direct_dependencies_of_roots();
+
+        let mut dual = dependency("a", "MIT", Verdict::Allow);
+        dual.licenses = LicenseExpression::any_of(["Apache 2.0", "MIT"]);
+        let mut overridden = dependency("b", "MIT", Verdict::Allow);
+        overridden.provenance = Some(Provenance::declared(LicenseSource::Override, None));
+        let dependencies = vec![dual, overridden];
+        document(
+            &dependencies,
+            &graph,
+            "Cargo.lock",
+            CreationInfo::at(1_700_000_000),
+            "1",
+        )
+    }
+
+    #[test]
+    fn packages_have_declared_and_concluded_licenses() {
+        let document = build();
+        assert_eq!(document.creation_info.created, "2023-11-14T22:13:20Z");
+        assert_eq!(
+            document.document_namespace,
+            "https://licensebat.com/spdxdocs/Cargo.lock-1"
+        );
+
+        let a = &document.packages[0];
+        assert_eq!(a.spdx_id, "SPDXRef-Package-a-1.0.0");
+        assert_eq!(
+            a.download_location,
+            "https://www.npmjs.com/package/a/v/1.0.0"
+        );
+        assert_eq!(a.license_concluded, "LicenseRef-Apache-2.0 OR MIT");
+        assert_eq!(a.license_declared, "LicenseRef-Apache-2.0 OR MIT");
+        assert_eq!(document.has_extracted_licensing_infos.len(), 1);
+        assert_eq!(
+            document.has_extracted_licensing_infos[0].extracted_text,
+            "Apache 2.0"
+        );
+
+        let b = &document.packages[1];
+        assert_eq!(b.license_concluded, "MIT");
+        assert_eq!(b.license_declared, NOASSERTION);
+
+        let app = &document.packages[2];
+        assert_eq!(app.spdx_id, "SPDXRef-Package-app-0.1.0");
+    }
+
+    #[test]
+    fn relationships_come_from_the_graph() {
+        let document = build();
+        let relationships = document
+            .relationships
+            .iter()
+            .map(|r| {
+                format!(
+                    "{} {} {}",
+                    r.spdx_element_id, r.relationship_type, r.related_spdx_element
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relationships,
+            vec![
+                "SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-app-0.1.0",
+                "SPDXRef-Package-app-0.1.0 DEPENDS_ON SPDXRef-Package-a-1.0.0",
+                "SPDXRef-Package-a-1.0.0 DEPENDS_ON SPDXRef-Package-b-1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_renders_tag_value() {
+        let tv = tag_value(&build());
+        assert!(tv.starts_with("SPDXVersion: SPDX-2.3\nDataLicense: CC0-1.0\n"));
+        assert!(tv.contains("Creator: Tool: licensebat-"));
+        assert!(tv.contains("PackageLicenseConcluded: LicenseRef-Apache-2.0 OR MIT\n"));
+        assert!(tv.contains("ExternalRef: PACKAGE-MANAGER purl pkg:npm/a@1.0.0\n"));
+        assert!(tv.contains("Relationship: SPDXRef-DOCUMENT DESCRIBES SPDXRef-Package-app-0.1.0\n"));
+        assert!(tv.contains(
+            "LicenseID: LicenseRef-Apache-2.0\nExtractedText: <text>Apache 2.0</text>\n"
+        ));
+    }
+
+    #[test]
+    fn license_refs_are_unique() {
+        let mut licenses = Licenses::default();
+        let mut dep = dependency("a", "MIT", Verdict::Allow);
+        dep.licenses = LicenseExpression::any_of(["Apache 2.0", "Apache/2.0", "Apache 2.0"]);
+        assert_eq!(
+            licenses.expression(&dep),
+            "LicenseRef-Apache-2.0 OR LicenseRef-Apache-2.0-2 OR LicenseRef-Apache-2.0"
+        );
+        assert_eq!(
+            licenses.extracted.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("LicenseRef-Apache-2.0".to_owned(), "Apache 2.0".to_owned()),
+                (
+                    "LicenseRef-Apache-2.0-2".to_owned(),
+                    "Apache/2.0".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn license_expressions_are_canonical() {
+        let mut licenses = Licenses::default();
+        let mut dep = dependency("a", "MIT or GPL-2.0+", Verdict::Allow);
+        assert_eq!(licenses.expression(&dep), "MIT OR GPL-2.0-or-later");
+
+        dep.licenses = LicenseExpression::any_of(["mit or apache-2.0", "GPL-3.0"]);
+        assert_eq!(
+            licenses.expression(&dep),
+            "LicenseRef-mit-or-apache-2.0 OR GPL-3.0-only"
+        );
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let mut ids = Ids::default();
+        assert_eq!(
+            ids.next("@acme/ui", "1.0.0"),
+            "SPDXRef-Package--acme-ui-1.0.0"
+        );
+        assert_eq!(
+            ids.next("@acme/ui", "1.0.0"),
+            "SPDXRef-Package--acme-ui-1.0.0-2"
+        );
+    }
+}
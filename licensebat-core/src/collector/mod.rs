@@ -0,0 +1,386 @@
+//! Collector traits.
+use crate::dependency::{Dependency, RetrievedDependency};
+use crate::graph::DependencyGraph;
+use crate::licrc::LicRc;
+use futures::{
+    future::{ready, BoxFuture},
+    stream::{FuturesUnordered, Stream},
+    Future, FutureExt, StreamExt,
+};
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+mod registry;
+
+pub use registry::CollectorRegistry;
+
+/// Default maximum number of dependencies retrieved at the same time by a [`RetrievedDependencyStream`].
+pub const DEFAULT_CONCURRENCY: usize = 100;
+
+/// Stream of [`RetrievedDependency`].
+///
+/// The futures retrieving the dependencies are lazily created from an iterator
+/// and only [`RetrievedDependencyStream::concurrency`] of them are polled at the same time.
+/// A new future won't be created until one of the running ones has finished, so the stream has natural backpressure:
+/// nothing is retrieved unless the stream is polled.
+///
+/// Note that dependencies are yielded as soon as they're retrieved, so the order is not preserved.
+pub struct RetrievedDependencyStream<'a> {
+    pending: Box<dyn Iterator<Item = BoxFuture<'a, RetrievedDependency>> + Send + 'a>,
+    in_flight: FuturesUnordered<BoxFuture<'a, RetrievedDependency>>,
+    concurrency: usize,
+}
+
+impl<'a> RetrievedDependencyStream<'a> {
+    /// Creates a new [`RetrievedDependencyStream`] from an iterator of futures.
+    ///
+    /// The iterator won't be consumed until the stream is polled.
+    /// By default, up to [`DEFAULT_CONCURRENCY`] futures will be polled at the same time.
+    #[must_use]
+    pub fn new<I, F>(futures: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        I::IntoIter: Send + 'a,
+        F: Future<Output = RetrievedDependency> + Send + 'a,
+    {
+        Self {
+            pending: Box::new(futures.into_iter().map(FutureExt::boxed).fuse()),
+            in_flight: FuturesUnordered::new(),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Creates the [`RetrievedDependencyStream`] of a [`FileCollector`] from the dependencies found in its dependency file.
+    ///
+    /// Every dependency comes with whatever the collector needs to retrieve it (i.e. its entry in the dependency file).
+    /// The overrides of the `.licrc` file are resolved right away, so the stream doesn't borrow it:
+    /// overridden dependencies are not retrieved but built with [`RetrievedDependency::overridden`], using `dependency_type` and `url`.
+    /// The rest are retrieved with `retrieve` as the stream is polled.
+    ///
+    /// Either way, the retrieved dependencies keep whether they're direct, their source and their Package URL,
+    /// and their licenses are normalized.
+    pub fn retrieve<T, F, Fut>(
+        dependencies: impl IntoIterator<Item = (T, Dependency)>,
+        licrc: &LicRc,
+        dependency_type: &'static str,
+        url: fn(&Dependency) -> Option<String>,
+        retrieve: F,
+    ) -> Self
+    where
+        T: Send + 'a,
+        F: Fn(T) -> Fut + Send + 'a,
+        Fut: Future<Output = RetrievedDependency> + Send + 'a,
+    {
+        let normalizer = licrc.licenses.normalizer();
+        let dependencies = dependencies
+            .into_iter()
+            .map(|(item, dependency)| {
+                let license = licrc.get_override(&dependency).map(str::to_owned);
+                (item, dependency, license)
+            })
+            .collect::<Vec<_>>();
+
+        Self::new(
+            dependencies
+                .into_iter()
+                .map(move |(item, dependency, license)| {
+                    let normalizer = normalizer.clone();
+                    let is_direct = dependency.is_direct;
+                    let (source, purl) = (dependency.source.clone(), dependency.purl.clone());
+                    let future = match license {
+                        Some(license) => {
+                            let url = url(&dependency);
+                            ready(RetrievedDependency::overridden(
+                                dependency,
+                                dependency_type,
+                                url,
+                                license,
+                            ))
+                            .boxed()
+                        }
+                        None => retrieve(item).boxed(),
+                    };
+                    future.map(move |retrieved| {
+                        normalizer.normalize_dependency(
+                            retrieved
+                                .with_is_direct(is_direct)
+                                .with_source(source, purl),
+                        )
+                    })
+                }),
+        )
+    }
+
+    /// Sets the maximum number of dependencies retrieved at the same time.
+    /// It will be at least 1.
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the maximum number of dependencies retrieved at the same time.
+    #[must_use]
+    pub const fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+}
+
+impl<'a> Stream for RetrievedDependencyStream<'a> {
+    type Item = RetrievedDependency;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        while this.in_flight.len() < this.concurrency {
+            match this.pending.next() {
+                Some(future) => this.in_flight.push(future),
+                None => break,
+            }
+        }
+        // if there's nothing in flight at this point, the iterator is exhausted
+        this.in_flight.poll_next_unpin(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.pending.size_hint();
+        let in_flight = self.in_flight.len();
+        (
+            lower.saturating_add(in_flight),
+            upper.and_then(|upper| upper.checked_add(in_flight)),
+        )
+    }
+}
+
+/// Result returning either a [`RetrievedDependencyStream`] or an [`Error`]
+pub type RetrievedDependencyStreamResult<'a> = Result<RetrievedDependencyStream<'a>, Error>;
+
+/// Error raised by a collector while parsing/getting the dependencies.
+///
+/// Collectors wrap the errors of their own parsers, so `licensebat-core` doesn't need to know about them.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Error produced when parsing a dependency file (Cargo.lock, package-lock.json, pubspec.yaml...).
+    #[error("Error parsing the {collector} dependency file{}: {source}", display_position(.position))]
+    Parse {
+        /// Name of the [`Collector`] that failed to parse the file.
+        collector: String,
+        /// Position of the error in the dependency file, if the parser informs it.
+        position: Option<Position>,
+        /// Error raised by the parser.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl Error {
+    /// Creates a new [`Error::Parse`] without position.
+    pub fn parse(
+        collector: impl Into<String>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        Self::Parse {
+            collector: collector.into(),
+            position: None,
+            source: source.into(),
+        }
+    }
+
+    /// Sets the position of the error in the dependency file.
+    #[must_use]
+    pub fn with_position(self, line: usize, column: usize) -> Self {
+        match self {
+            Self::Parse {
+                collector, source, ..
+            } => Self::Parse {
+                collector,
+                position: Some(Position { line, column }),
+                source,
+            },
+        }
+    }
+
+    /// Gets the position of the error in the dependency file, if known.
+    #[must_use]
+    pub const fn position(&self) -> Option<Position> {
+        match self {
+            Self::Parse { position, .. } => *position,
+        }
+    }
+}
+
+/// Position (1-based) in a dependency file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// Line number.
+    pub line: usize,
+    /// Column number.
+    pub column: usize,
+}
+
+impl std::fmt::Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+fn display_position(position: &Option<Position>) -> String {
+    position
+        .map(|position| format!(" at {position}"))
+        .unwrap_or_default()
+}
+
+/// Base trait for collectors.
+pub trait Collector: Debug + Send + Sync {
+    /// Gets the name of the [`Collector`] (npm, dart, rust, go, python...).
+    fn get_name(&self) -> String;
+}
+
+/// Trait to be implemented for every [`Collector`] dealing with a dependency file (package-lock.json, pubspec.yaml...).
+pub trait FileCollector: Collector {
+    /// Gets the name of the file holding all the dependencies.
+    /// i.e. for npm package-lock.json, for rust cargo.lock
+    fn get_dependency_filename(&self) -> String;
+    /// Gets the patterns of the names of the files supported by the collector.
+    /// They can be exact file names (`package-lock.json`) or contain `*` wildcards (`*.lock`).
+    ///
+    /// By default, only the file returned by [`FileCollector::get_dependency_filename`] is supported.
+    fn get_dependency_file_patterns(&self) -> Vec<String> {
+        vec![self.get_dependency_filename()]
+    }
+    /// Gets the priority of the collector.
+    /// When several collectors support the same file, the one with the highest priority will be used.
+    ///
+    /// It's 0 by default.
+    fn get_priority(&self) -> i32 {
+        0
+    }
+    /// Returns a stream of [`RetrievedDependency`] ready to be validated.
+    /// It accepts a &str with the content of the dependency file
+    /// and a function to filter the dependencies that we don't want to process,
+    /// mainly because the user has expressed the will to not to do so.
+    /// # Errors
+    ///
+    /// Will return an [`Error`] if the parsing of the dependency file fails.
+    fn get_dependencies(
+        &self,
+        dependency_file_content: &str,
+        licrc: &LicRc,
+    ) -> RetrievedDependencyStreamResult<'_>;
+    /// Builds the [`DependencyGraph`] described by the dependency file.
+    ///
+    /// By default, an empty graph is returned, meaning that the collector doesn't support it.
+    /// # Errors
+    ///
+    /// Will return an [`Error`] if the parsing of the dependency file fails.
+    fn get_dependency_graph(
+        &self,
+        _dependency_file_content: &str,
+    ) -> Result<DependencyGraph, Error> {
+        Ok(DependencyGraph::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    fn dependency(name: usize) -> RetrievedDependency {
+        RetrievedDependency {
+            name: name.to_string(),
+            ..RetrievedDependency::default()
+        }
+    }
+
+    #[test]
+    fn futures_are_created_lazily_and_bounded() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        let mut stream = RetrievedDependencyStream::new((0..10).map(move |i| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            ready(dependency(i))
+        }))
+        .with_concurrency(3);
+        assert_eq!(created.load(Ordering::SeqCst), 0);
+        assert_eq!(stream.size_hint(), (10, Some(10)));
+
+        assert!(block_on(stream.next()).is_some());
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+
+        let rest = block_on(stream.collect::<Vec<_>>());
+        assert_eq!(rest.len(), 9);
+        assert_eq!(created.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn overridden_dependencies_are_not_retrieved() {
+        let mut licrc = LicRc::default();
+        licrc.dependencies.overrides = Some([("foo".to_owned(), "mit".to_owned())].into());
+        let dependencies = ["foo", "bar"].map(|name| {
+            let dependency = Dependency {
+                is_direct: Some(true),
+                purl: Some(format!("pkg:npm/{name}@1.0.0")),
+                ..Dependency::new(name, "1.0.0")
+            };
+            (name, dependency)
+        });
+        let stream = RetrievedDependencyStream::retrieve(
+            dependencies,
+            &licrc,
+            "npm",
+            |dependency| Some(format!("https://www.npmjs.com/package/{}", dependency.name)),
+            |name| {
+                assert_eq!(name, "bar");
+                ready(RetrievedDependency {
+                    name: name.to_owned(),
+                    ..RetrievedDependency::default()
+                })
+            },
+        );
+
+        let mut retrieved = block_on(stream.collect::<Vec<_>>());
+        retrieved.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(retrieved[0].name, "bar");
+        assert!(!retrieved[0].is_overridden);
+        assert_eq!(retrieved[1].name, "foo");
+        assert!(retrieved[1].is_overridden);
+        assert_eq!(
+            retrieved[1].url.as_deref(),
+            Some("https://www.npmjs.com/package/foo")
+        );
+        assert_eq!(
+            retrieved[1].licenses,
+            Some(crate::license::LicenseExpression::license("MIT"))
+        );
+        assert!(retrieved
+            .iter()
+            .all(|dep| dep.is_direct == Some(true) && dep.purl.is_some()));
+    }
+
+    #[test]
+    fn concurrency_is_at_least_one() {
+        let stream = RetrievedDependencyStream::new(Vec::<BoxFuture<'_, _>>::new());
+        assert_eq!(stream.concurrency(), DEFAULT_CONCURRENCY);
+        assert_eq!(stream.with_concurrency(0).concurrency(), 1);
+    }
+
+    #[test]
+    fn parse_errors_keep_their_source_and_position() {
+        let source = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let error = Error::parse("npm", source).with_position(1, 2);
+
+        assert_eq!(error.position(), Some(Position { line: 1, column: 2 }));
+        assert!(error
+            .to_string()
+            .starts_with("Error parsing the npm dependency file at line 1, column 2: "));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
@@ -0,0 +1,169 @@
+use super::FileCollector;
+use crate::licrc::wildcard_match;
+use std::path::Path;
+
+/// Registry of [`FileCollector`]s able to resolve the right one for a dependency file.
+///
+/// Collectors are matched by the name of the file (not the whole path) against their [`FileCollector::get_dependency_file_patterns`].
+/// If several collectors support the same file, the one with the highest [`FileCollector::get_priority`] wins
+/// and, in case of a tie, the one registered first.
+///
+/// ```
+/// # use licensebat_core::CollectorRegistry;
+/// let registry = CollectorRegistry::new();
+/// assert!(registry.resolve("./Cargo.lock").is_none());
+/// ```
+#[derive(Debug, Default)]
+pub struct CollectorRegistry {
+    collectors: Vec<Box<dyn FileCollector>>,
+}
+
+impl CollectorRegistry {
+    /// Creates a new empty [`CollectorRegistry`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a [`FileCollector`] to the registry.
+    #[must_use]
+    pub fn with(mut self, collector: impl FileCollector + 'static) -> Self {
+        self.register(collector);
+        self
+    }
+
+    /// Adds a [`FileCollector`] to the registry.
+    pub fn register(&mut self, collector: impl FileCollector + 'static) -> &mut Self {
+        self.collectors.push(Box::new(collector));
+        self
+    }
+
+    /// Returns the registered [`FileCollector`]s.
+    pub fn collectors(&self) -> impl Iterator<Item = &dyn FileCollector> {
+        self.collectors.iter().map(AsRef::as_ref)
+    }
+
+    /// Returns the [`FileCollector`] that must be used for a dependency file, if any.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> Option<&dyn FileCollector> {
+        let file_name = path.as_ref().file_name()?.to_str()?;
+        self.collectors
+            .iter()
+            .map(AsRef::as_ref)
+            // `max_by_key` returns the last element in case of a tie
+            .rev()
+            .filter(|collector| {
+                collector
+                    .get_dependency_file_patterns()
+                    .iter()
+                    .any(|pattern| wildcard_match(pattern, file_name))
+            })
+            .max_by_key(|collector| collector.get_priority())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        collector::{RetrievedDependencyStream, RetrievedDependencyStreamResult},
+        licrc::LicRc,
+        Collector,
+    };
+    use futures::future::BoxFuture;
+
+    #[derive(Debug)]
+    struct MockCollector {
+        name: &'static str,
+        patterns: &'static [&'static str],
+        priority: i32,
+    }
+
+    impl Collector for MockCollector {
+        fn get_name(&self) -> String {
+            self.name.to_owned()
+        }
+    }
+
+    impl FileCollector for MockCollector {
+        fn get_dependency_filename(&self) -> String {
+            self.patterns[0].to_owned()
+        }
+
+        fn get_dependency_file_patterns(&self) -> Vec<String> {
+            self.patterns.iter().map(ToString::to_string).collect()
+        }
+
+        fn get_priority(&self) -> i32 {
+            self.priority
+        }
+
+        fn get_dependencies(
+            &self,
+            _dependency_file_content: &str,
+            _licrc: &LicRc,
+        ) -> RetrievedDependencyStreamResult<'_> {
+            Ok(RetrievedDependencyStream::new(
+                Vec::<BoxFuture<'_, _>>::new(),
+            ))
+        }
+    }
+
+    fn resolve(registry: &CollectorRegistry, path: &str) -> Option<String> {
+        registry.resolve(path).map(Collector::get_name)
+    }
+
+    #[test]
+    fn collectors_are_resolved_by_file_name() {
+        let registry = CollectorRegistry::new()
+            .with(MockCollector {
+                name: "npm",
+                patterns: &["package-lock.json", "npm-shrinkwrap.json"],
+                priority: 0,
+            })
+            .with(MockCollector {
+                name: "rust",
+                patterns: &["Cargo.lock"],
+                priority: 0,
+            });
+
+        assert_eq!(
+            resolve(&registry, "package-lock.json").as_deref(),
+            Some("npm")
+        );
+        assert_eq!(
+            resolve(&registry, "./app/npm-shrinkwrap.json").as_deref(),
+            Some("npm")
+        );
+        assert_eq!(resolve(&registry, "../Cargo.lock").as_deref(), Some("rust"));
+        assert_eq!(resolve(&registry, "Cargo.lock.bak"), None);
+        assert_eq!(resolve(&registry, "Cargo.lock/other.txt"), None);
+    }
+
+    #[test]
+    fn highest_priority_wins() {
+        let mut registry = CollectorRegistry::new();
+        registry
+            .register(MockCollector {
+                name: "generic",
+                patterns: &["*.lock"],
+                priority: 0,
+            })
+            .register(MockCollector {
+                name: "other",
+                patterns: &["*.lock"],
+                priority: 0,
+            })
+            .register(MockCollector {
+                name: "yarn",
+                patterns: &["yarn.lock"],
+                priority: 10,
+            });
+
+        assert_eq!(resolve(&registry, "yarn.lock").as_deref(), Some("yarn"));
+        assert_eq!(
+            resolve(&registry, "pubspec.lock").as_deref(),
+            Some("generic")
+        );
+        assert_eq!(registry.collectors().count(), 3);
+    }
+}
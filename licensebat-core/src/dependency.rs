@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use crate::license::{Category, LicenseExpression};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Debug;
 
 /// Generic and plain dependency without any extra information.
@@ -13,16 +14,25 @@ pub struct Dependency {
     pub is_dev: Option<bool>,
     /// True if the dependency is an optional dependency, false otherwise. Null if we cannot determine it.
     pub is_optional: Option<bool>,
+    /// True if the project depends on it directly, false if it's a transitive dependency. Null if we cannot determine it.
+    pub is_direct: Option<bool>,
+    /// Description of where the dependency comes from, as declared in the dependency file (i.e. a registry or a git repository).
+    pub source: Option<String>,
+    /// [Package URL](https://github.com/package-url/purl-spec) of the dependency (see [`crate::purl::PackageUrl`]).
+    pub purl: Option<String>,
 }
 
 impl Dependency {
-    /// Creates a new dependency without dev or optional information.
+    /// Creates a new dependency without dev, optional or direct information.
     pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
         Self {
             name: name.into(),
             version: version.into(),
             is_dev: None,
             is_optional: None,
+            is_direct: None,
+            source: None,
+            purl: None,
         }
     }
 }
@@ -30,7 +40,10 @@ impl Dependency {
 /// A dependency that has been retrieved from its source.
 /// The source can be anything, from a third party API (i.e. npm, pub.dev or crates.io APIs) to the file system.
 /// It holds information about licenses, errors while validating...
+///
+/// Dependencies serialized before verdicts existed don't have a `verdict`, so it's deduced from `is_valid`.
 #[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd, Clone, Default)]
+#[serde(remote = "Self")]
 pub struct RetrievedDependency {
     /// Dependency name.
     pub name: String,
@@ -41,21 +54,36 @@ pub struct RetrievedDependency {
     /// Url of the dependency if available.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
-    /// List of licenses of the dependency.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub licenses: Option<Vec<String>>,
+    /// Licenses of the dependency, keeping the declarations as found in its source.
+    ///
+    /// It's serialized as the list of declared `licenses` plus the whole `license_expression` string.
+    #[serde(flatten, with = "license_fields")]
+    pub licenses: Option<LicenseExpression>,
     /// Set to true if the dependency has been validated against the licrc.
     pub validated: bool,
     /// Indicates if the license is valid for our project or not according to our .licrc configuration file.
+    /// It's `false` only when the [`Verdict`] is [`Verdict::Deny`].
     pub is_valid: bool,
+    /// Result of the validation against our .licrc configuration file.
+    #[serde(skip_deserializing)]
+    pub verdict: Verdict,
     /// Indicates if the dependency has been ignored according to our .licrc configuration file.
     pub is_ignored: bool,
+    /// Indicates if the dependency matches an exception of the .licrc file that has expired, so it's no longer ignored.
+    #[serde(default)]
+    pub is_exception_expired: bool,
     /// Contains information about any error that may have happened during the validation process.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Reason of the error, meant to be used by tools instead of the `error` message.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<FailureReason>,
     /// Comments about the license validation process.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<Comment>,
+    /// Describes how the licenses have been determined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
     /// In cases where the retriever makes some sort of estimate about the license, this field will contain the suggested licenses.
     pub suggested_licenses: Option<Vec<(String, f32)>>,
     /// Indicates if the dependency is a dev dependency or not. This can be null if we cannot determine it.
@@ -64,6 +92,53 @@ pub struct RetrievedDependency {
     /// Indicates if the dependency is an optional dependency or not. This can be null if we cannot determine it.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_optional: Option<bool>,
+    /// Indicates if the project depends directly on the dependency or not. This can be null if we cannot determine it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_direct: Option<bool>,
+    /// Description of where the dependency comes from (i.e. a registry or a git repository).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// [Package URL](https://github.com/package-url/purl-spec) of the dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purl: Option<String>,
+    /// Indicates if the license has been declared by an override in the `.licrc` file instead of being retrieved from its source.
+    #[serde(default)]
+    pub is_overridden: bool,
+    /// Category of the licenses that have been used to validate the dependency.
+    /// If the dependency declares several licenses, the most restrictive category will be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    /// Policy of the .licrc file that has been used to validate the dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyScope>,
+}
+
+impl Serialize for RetrievedDependency {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for RetrievedDependency {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct WithVerdict {
+            #[serde(flatten, with = "RetrievedDependency")]
+            dependency: RetrievedDependency,
+            verdict: Option<Verdict>,
+        }
+
+        let WithVerdict {
+            mut dependency,
+            verdict,
+        } = WithVerdict::deserialize(deserializer)?;
+        dependency.verdict = verdict.unwrap_or(if dependency.is_valid {
+            Verdict::Allow
+        } else {
+            Verdict::Deny
+        });
+        Ok(dependency)
+    }
 }
 
 impl RetrievedDependency {
@@ -79,7 +154,7 @@ impl RetrievedDependency {
         version: String,
         dependency_type: String,
         url: Option<String>,
-        licenses: Option<Vec<String>>,
+        licenses: Option<LicenseExpression>,
         error: Option<String>,
         comment: Option<Comment>,
         suggested_licenses: Option<Vec<(String, f32)>>,
@@ -87,6 +162,7 @@ impl RetrievedDependency {
         is_optional: Option<bool>,
     ) -> Self {
         let has_licenses = licenses.is_some();
+        let is_valid = has_licenses && error.is_none();
 
         Self {
             name,
@@ -95,8 +171,21 @@ impl RetrievedDependency {
             url,
             licenses,
             validated: false,
-            is_valid: has_licenses && error.is_none(),
+            is_valid,
+            verdict: if is_valid {
+                Verdict::Allow
+            } else {
+                Verdict::Deny
+            },
             is_ignored: false,
+            is_exception_expired: false,
+            failure_reason: if error.is_some() {
+                Some(FailureReason::Other)
+            } else if has_licenses {
+                None
+            } else {
+                Some(FailureReason::NoLicenseDeclared)
+            },
             error: error.or_else(|| {
                 if has_licenses {
                     None
@@ -111,13 +200,289 @@ impl RetrievedDependency {
                     Some(Comment::removable("Consider manually checking this dependency's license. Remember this: https://choosealicense.com/no-permission/ and ignore it if you feel confident about it to avoid this warning."))
                 }
             }),
+            provenance: None,
             suggested_licenses,
             is_dev,
             is_optional,
+            is_direct: None,
+            source: None,
+            purl: None,
+            is_overridden: false,
+            category: None,
+            policy: None,
+        }
+    }
+
+    /// Creates a new `RetrievedDependency` whose license has been declared by an override in the `.licrc` file.
+    /// Collectors use this method instead of calling their retrievers when an override exists.
+    #[must_use]
+    pub fn overridden(
+        dependency: Dependency,
+        dependency_type: impl Into<String>,
+        url: Option<String>,
+        license: impl Into<String>,
+    ) -> Self {
+        let is_direct = dependency.is_direct;
+        let source = dependency.source.clone();
+        let purl = dependency.purl.clone();
+        let mut retrieved = Self::new(
+            dependency.name,
+            dependency.version,
+            dependency_type.into(),
+            url,
+            Some(LicenseExpression::license(license)),
+            None,
+            Some(Comment::non_removable(
+                "License declared by an override in the .licrc file.",
+            )),
+            None,
+            dependency.is_dev,
+            dependency.is_optional,
+        );
+        retrieved.is_overridden = true;
+        retrieved
+            .with_provenance(Provenance::declared(LicenseSource::Override, None))
+            .with_is_direct(is_direct)
+            .with_source(source, purl)
+    }
+
+    /// Sets whether the project depends directly on the dependency or not.
+    ///
+    /// Collectors use it as they are the ones knowing the structure of the dependency file.
+    #[must_use]
+    pub const fn with_is_direct(mut self, is_direct: Option<bool>) -> Self {
+        self.is_direct = is_direct;
+        self
+    }
+
+    /// Sets the [`Verdict`] of the dependency, keeping `is_valid` consistent with it.
+    #[must_use]
+    pub fn with_verdict(mut self, verdict: Verdict) -> Self {
+        self.is_valid = verdict != Verdict::Deny;
+        self.verdict = verdict;
+        self
+    }
+
+    /// Sets where the dependency comes from and its Package URL.
+    ///
+    /// Collectors use it as they are the ones knowing the sources declared in the dependency file.
+    #[must_use]
+    pub fn with_source(mut self, source: Option<String>, purl: Option<String>) -> Self {
+        self.source = source;
+        self.purl = purl;
+        self
+    }
+
+    /// Flags the dependency for review with a comment explaining why, added to the existing non removable comment.
+    /// It will be reported as a warning unless its licenses are not compliant.
+    ///
+    /// Retrievers use it when they're not confident enough about the licenses they have detected.
+    #[must_use]
+    pub fn flagged_for_review(mut self, comment: impl Into<String>) -> Self {
+        if self.verdict == Verdict::Allow {
+            self.verdict = Verdict::Warn;
+        }
+        self.add_comment(comment.into());
+        self
+    }
+
+    /// Adds a non removable comment, keeping the previous non removable one.
+    pub(crate) fn add_comment(&mut self, text: String) {
+        self.comment = Some(match self.comment.take() {
+            Some(comment) if !comment.remove_when_valid => {
+                Comment::non_removable(format!("{} {text}", comment.text))
+            }
+            _ => Comment::non_removable(text),
+        });
+    }
+
+    /// Sets the [`Provenance`] of the licenses of the dependency.
+    #[must_use]
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Sets the [`FailureReason`] of the dependency.
+    /// Retrievers use it to tell why the dependency couldn't be retrieved, as [`RetrievedDependency::new`] can only guess it.
+    #[must_use]
+    pub fn with_failure_reason(mut self, reason: FailureReason) -> Self {
+        self.failure_reason = Some(reason);
+        self
+    }
+}
+
+/// Result of the validation of a [`RetrievedDependency`].
+///
+/// Variants are sorted from the worst to the best result.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// The dependency is not compliant with the policy and it should block the check.
+    #[default]
+    Deny,
+    /// The dependency is compliant but its license deserves some attention. It doesn't block the check.
+    Warn,
+    /// The dependency is compliant with the policy.
+    Allow,
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Deny => "deny",
+            Self::Warn => "warn",
+            Self::Allow => "allow",
+        })
+    }
+}
+
+/// Describes how the licenses of a [`RetrievedDependency`] have been determined,
+/// so they can be trusted or challenged.
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd, Clone)]
+pub struct Provenance {
+    /// Where the licenses have been found.
+    pub source: LicenseSource,
+    /// Url or file that has been examined, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// How the licenses have been detected.
+    pub detector: Detector,
+    /// Confidence in the detected licenses, from 0 to 1.
+    pub confidence: f32,
+}
+
+impl Provenance {
+    /// Creates a new [`Provenance`] for licenses declared as is by the source, so the confidence is 1.
+    #[must_use]
+    pub fn declared(source: LicenseSource, location: Option<String>) -> Self {
+        Self {
+            source,
+            location,
+            detector: Detector::Declared,
+            confidence: 1.0,
+        }
+    }
+
+    /// Creates a new [`Provenance`] for a license detected by analyzing the text of a license file with [askalono](https://github.com/jpeddicord/askalono).
+    #[must_use]
+    pub fn askalono(location: impl Into<String>, score: f32) -> Self {
+        Self {
+            source: LicenseSource::LicenseFile,
+            location: Some(location.into()),
+            detector: Detector::Askalono,
+            confidence: score,
         }
     }
 }
 
+/// Where the licenses of a [`RetrievedDependency`] have been found.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseSource {
+    /// The metadata exposed by the registry (i.e. npm, crates.io or pub.dev).
+    Registry,
+    /// The manifest of the package (i.e. `Cargo.toml`).
+    Manifest,
+    /// The license file of the package.
+    LicenseFile,
+    /// The license assumed for some kind of dependencies (i.e. Dart SDK dependencies).
+    Default,
+    /// An override declared in the .licrc file.
+    Override,
+}
+
+/// How the licenses of a [`RetrievedDependency`] have been detected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Detector {
+    /// The licenses are declared as is by the source.
+    Declared,
+    /// The license text has been analyzed with [askalono](https://github.com/jpeddicord/askalono).
+    Askalono,
+    /// The licenses haven't been detected but assumed.
+    Assumed,
+}
+
+/// Reason why a dependency has failed to be retrieved or validated.
+///
+/// The `error` field of the [`RetrievedDependency`] keeps the message to be shown to the users.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// The source of the dependency couldn't be reached.
+    NetworkError,
+    /// The dependency (or its version) doesn't exist in its source.
+    NotFound,
+    /// The dependency doesn't declare any license.
+    NoLicenseDeclared,
+    /// The licenses of the dependency are not compliant with the .licrc file.
+    NotCompliant,
+    /// The source of the dependency (i.e. git or a local path) is not supported.
+    UnsupportedSource,
+    /// The information about the dependency couldn't be parsed.
+    ParseError,
+    /// Any other error.
+    Other,
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::NetworkError => "network_error",
+            Self::NotFound => "not_found",
+            Self::NoLicenseDeclared => "no_license_declared",
+            Self::NotCompliant => "not_compliant",
+            Self::UnsupportedSource => "unsupported_source",
+            Self::ParseError => "parse_error",
+            Self::Other => "other",
+        })
+    }
+}
+
+impl FailureReason {
+    /// Returns the reason of a failed HTTP request given the status of the response, if any.
+    #[must_use]
+    pub fn from_http_status(status: Option<u16>) -> Self {
+        match status {
+            Some(404 | 410) => Self::NotFound,
+            _ => Self::NetworkError,
+        }
+    }
+}
+
+/// Scope of the .licrc policy used to validate a dependency.
+///
+/// Dev, optional and transitive dependencies are validated against the `[licenses.dev]`, `[licenses.optional]` and `[licenses.transitive]` sections when they exist.
+#[derive(
+    Serialize, Deserialize, Debug, Default, Clone, Copy, Eq, Ord, PartialEq, PartialOrd, Hash,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyScope {
+    /// The main policy, declared in the `[licenses]` section.
+    #[default]
+    Default,
+    /// The policy for dev dependencies, declared in the `[licenses.dev]` section.
+    Dev,
+    /// The policy for optional dependencies, declared in the `[licenses.optional]` section.
+    Optional,
+    /// The policy for transitive dependencies, declared in the `[licenses.transitive]` section.
+    Transitive,
+}
+
+impl std::fmt::Display for PolicyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Default => "default",
+            Self::Dev => "dev",
+            Self::Optional => "optional",
+            Self::Transitive => "transitive",
+        })
+    }
+}
+
 /// A comment to be added in a [`RetrievedDependency`] once it has been retrieved or validated.
 /// It normally adds information about what went wrong.
 #[derive(Serialize, Deserialize, Debug, Default, Eq, Ord, PartialEq, PartialOrd, Clone)]
@@ -147,3 +512,148 @@ impl Comment {
         }
     }
 }
+
+/// Keeps the serialized shape of [`RetrievedDependency::licenses`]: `licenses` is still the list of declarations
+/// and the way they are combined is exposed as a separate `license_expression` string (i.e. `MIT OR Apache-2.0`).
+///
+/// The expression string is only informative, so the whole [`LicenseExpression`] is kept in `declared_licenses`
+/// to deserialize it without losses. Payloads without it consider that all the `licenses` apply, as they used to.
+mod license_fields {
+    use crate::license::LicenseExpression;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct LicenseFields {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        licenses: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        license_expression: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        declared_licenses: Option<LicenseExpression>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        licenses: &Option<LicenseExpression>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        LicenseFields {
+            licenses: licenses
+                .as_ref()
+                .map(|l| l.licenses().into_iter().map(ToOwned::to_owned).collect()),
+            license_expression: licenses.as_ref().map(ToString::to_string),
+            declared_licenses: licenses.clone(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<LicenseExpression>, D::Error> {
+        let fields = LicenseFields::deserialize(deserializer)?;
+        Ok(fields
+            .declared_licenses
+            .or_else(|| fields.licenses.and_then(LicenseExpression::all_of)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flagging_for_review_keeps_the_previous_comment() {
+        let dependency = RetrievedDependency {
+            comment: Some(Comment::non_removable("Detected by its license file.")),
+            ..RetrievedDependency::default()
+        }
+        .with_verdict(Verdict::Allow)
+        .flagged_for_review("Please review it.");
+        assert_eq!(dependency.verdict, Verdict::Warn);
+        assert_eq!(
+            dependency.comment,
+            Some(Comment::non_removable(
+                "Detected by its license file. Please review it."
+            ))
+        );
+    }
+
+    #[test]
+    fn licenses_are_serialized_as_a_list_and_an_expression() {
+        let dependency = RetrievedDependency {
+            name: "dep".to_owned(),
+            licenses: LicenseExpression::any_of(["MIT", "Apache-2.0"]),
+            ..RetrievedDependency::default()
+        };
+        let json = serde_json::to_value(&dependency).unwrap();
+        assert_eq!(json["licenses"], serde_json::json!(["MIT", "Apache-2.0"]));
+        assert_eq!(json["license_expression"], "MIT OR Apache-2.0");
+
+        let json = serde_json::to_value(RetrievedDependency::default()).unwrap();
+        assert!(json.get("licenses").is_none());
+        assert!(json.get("license_expression").is_none());
+        assert!(json.get("declared_licenses").is_none());
+    }
+
+    #[test]
+    fn any_of_licenses_survive_a_round_trip() {
+        let dependency = RetrievedDependency {
+            name: "dep".to_owned(),
+            licenses: LicenseExpression::any_of(["MIT", "GPL-3.0-only"]),
+            ..RetrievedDependency::default()
+        };
+        let json = serde_json::to_string(&dependency).unwrap();
+        let deserialized: RetrievedDependency = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.licenses, dependency.licenses);
+    }
+
+    #[test]
+    fn licenses_are_deserialized_from_the_list() {
+        let dependency: RetrievedDependency = serde_json::from_value(serde_json::json!({
+            "name": "dep",
+            "version": "1.0.0",
+            "dependency_type": "npm",
+            "licenses": ["MIT", "Apache-2.0"],
+            "validated": true,
+            "is_valid": true,
+            "is_ignored": false,
+            "suggested_licenses": null,
+        }))
+        .unwrap();
+        assert_eq!(
+            dependency.licenses,
+            LicenseExpression::all_of(["MIT", "Apache-2.0"])
+        );
+    }
+
+    #[test]
+    fn missing_verdicts_are_deduced_from_is_valid() {
+        let json = |is_valid: bool| {
+            serde_json::json!({
+                "name": "dep",
+                "version": "1.0.0",
+                "dependency_type": "npm",
+                "validated": true,
+                "is_valid": is_valid,
+                "is_ignored": false,
+                "suggested_licenses": null,
+            })
+        };
+        let valid: RetrievedDependency = serde_json::from_value(json(true)).unwrap();
+        assert_eq!(valid.verdict, Verdict::Allow);
+        let invalid: RetrievedDependency = serde_json::from_value(json(false)).unwrap();
+        assert_eq!(invalid.verdict, Verdict::Deny);
+
+        let mut warned = json(true);
+        warned["verdict"] = serde_json::json!("warn");
+        let warned: RetrievedDependency = serde_json::from_value(warned).unwrap();
+        assert_eq!(warned.verdict, Verdict::Warn);
+
+        let dependency = RetrievedDependency::default().with_verdict(Verdict::Warn);
+        let json = serde_json::to_value(&dependency).unwrap();
+        assert_eq!(json["verdict"], "warn");
+        assert_eq!(
+            serde_json::from_value::<RetrievedDependency>(json).unwrap(),
+            dependency
+        );
+    }
+}
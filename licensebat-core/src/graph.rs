@@ -0,0 +1,283 @@
+//! Dependency graph of a project.
+//!
+//! A [`DependencyGraph`] is built by a [`FileCollector`] from the edge information contained in the dependency file.
+//!
+//! It can be used to know which direct dependencies introduced a transitive one.
+//!
+//! [`FileCollector`]: crate::FileCollector
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Identifier of a node in a [`DependencyGraph`].
+pub type NodeId = usize;
+
+/// Node of a [`DependencyGraph`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    /// Dependency name.
+    pub name: String,
+    /// Dependency version.
+    pub version: String,
+    /// Whether the node is a root of the graph (the project itself or one of its workspace members).
+    pub is_root: bool,
+    /// Whether the node is a direct dependency of a root.
+    pub is_direct: bool,
+}
+
+/// Edge of a [`DependencyGraph`]. The `from` node depends on the `to` node.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GraphEdge {
+    /// Dependent node.
+    pub from: NodeId,
+    /// Dependency node.
+    pub to: NodeId,
+}
+
+/// Graph of the dependencies of a project.
+///
+/// Nodes are unique by name and version.
+///
+/// # Example
+///
+/// ```
+/// use licensebat_core::DependencyGraph;
+///
+/// let mut graph = DependencyGraph::new();
+/// let app = graph.add_node("app", "1.0.0");
+/// let direct = graph.add_node("direct", "1.0.0");
+/// let transitive = graph.add_node("transitive", "2.0.0");
+/// graph.mark_root(app);
+/// graph.add_edge(app, direct);
+/// graph.add_edge(direct, transitive);
+/// graph.mark_direct_dependencies_of_roots();
+///
+/// assert_eq!(graph.introduced_by(transitive), vec![direct]);
+/// ```
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DependencyGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    #[serde(skip)]
+    index: HashMap<(String, String), NodeId>,
+    #[serde(skip)]
+    edge_index: HashSet<GraphEdge>,
+    /// Adjacency lists indexed by [`NodeId`], so the graph can be walked without scanning all the edges.
+    #[serde(skip)]
+    dependencies: Vec<Vec<NodeId>>,
+    #[serde(skip)]
+    dependents: Vec<Vec<NodeId>>,
+}
+
+impl DependencyGraph {
+    /// Creates a new empty [`DependencyGraph`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the graph has no nodes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Gets all the nodes of the graph. Their position is their [`NodeId`].
+    #[must_use]
+    pub fn nodes(&self) -> &[GraphNode] {
+        &self.nodes
+    }
+
+    /// Gets all the edges of the graph.
+    #[must_use]
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    /// Gets a node by its id.
+    #[must_use]
+    pub fn node(&self, id: NodeId) -> Option<&GraphNode> {
+        self.nodes.get(id)
+    }
+
+    /// Finds the id of a node by its name and version.
+    #[must_use]
+    pub fn find(&self, name: &str, version: &str) -> Option<NodeId> {
+        self.index
+            .get(&(name.to_owned(), version.to_owned()))
+            .copied()
+    }
+
+    /// Returns whether a dependency is a direct one or a transitive one.
+    ///
+    /// It will be `None` if the dependency is not in the graph or if it's a root.
+    #[must_use]
+    pub fn is_direct(&self, name: &str, version: &str) -> Option<bool> {
+        self.find(name, version)
+            .map(|id| &self.nodes[id])
+            .filter(|node| !node.is_root)
+            .map(|node| node.is_direct)
+    }
+
+    /// Adds a node to the graph and returns its id.
+    ///
+    /// If a node with the same name and version already exists, its id will be returned.
+    pub fn add_node(&mut self, name: impl Into<String>, version: impl Into<String>) -> NodeId {
+        let key = (name.into(), version.into());
+        if let Some(id) = self.index.get(&key) {
+            return *id;
+        }
+        let id = self.nodes.len();
+        self.nodes.push(GraphNode {
+            name: key.0.clone(),
+            version: key.1.clone(),
+            is_root: false,
+            is_direct: false,
+        });
+        self.dependencies.push(vec![]);
+        self.dependents.push(vec![]);
+        self.index.insert(key, id);
+        id
+    }
+
+    /// Adds an edge meaning that `from` depends on `to`.
+    /// Duplicated edges, self references and edges between unknown nodes are ignored.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        let edge = GraphEdge { from, to };
+        let is_known = from < self.nodes.len() && to < self.nodes.len();
+        if is_known && from != to && self.edge_index.insert(edge) {
+            self.edges.push(edge);
+            self.dependencies[from].push(to);
+            self.dependents[to].push(from);
+        }
+    }
+
+    /// Marks a node as a root of the graph.
+    pub fn mark_root(&mut self, id: NodeId) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.is_root = true;
+        }
+    }
+
+    /// Marks a node as a direct dependency.
+    pub fn mark_direct(&mut self, id: NodeId) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.is_direct = true;
+        }
+    }
+
+    /// Marks every dependency of a root node as a direct dependency.
+    pub fn mark_direct_dependencies_of_roots(&mut self) {
+        let direct = self
+            .roots()
+            .flat_map(|root| self.dependencies_of(root))
+            .filter(|id| !self.nodes[*id].is_root)
+            .collect::<Vec<_>>();
+        for id in direct {
+            self.mark_direct(id);
+        }
+    }
+
+    /// Gets the ids of the root nodes.
+    pub fn roots(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|id| self.nodes[*id].is_root)
+    }
+
+    /// Gets the ids of the direct dependencies.
+    pub fn direct_dependencies(&self) -> impl Iterator<Item = NodeId> + '_ {
+        (0..self.nodes.len()).filter(|id| self.nodes[*id].is_direct)
+    }
+
+    /// Gets the ids of the nodes `id` depends on.
+    pub fn dependencies_of(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.dependencies.get(id).into_iter().flatten().copied()
+    }
+
+    /// Gets the ids of the nodes depending on `id`.
+    pub fn dependents_of(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.dependents.get(id).into_iter().flatten().copied()
+    }
+
+    /// Gets the ids of the direct dependencies that (transitively) introduced the node `id`, sorted.
+    ///
+    /// It will be empty for roots, direct dependencies only reachable from roots and nodes without dependents.
+    #[must_use]
+    pub fn introduced_by(&self, id: NodeId) -> Vec<NodeId> {
+        let mut visited = HashSet::from([id]);
+        let mut queue = VecDeque::from([id]);
+        let mut direct = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for dependent in self.dependents_of(current) {
+                let node = &self.nodes[dependent];
+                if node.is_root || !visited.insert(dependent) {
+                    continue;
+                }
+                if node.is_direct {
+                    direct.push(dependent);
+                }
+                queue.push_back(dependent);
+            }
+        }
+        direct.sort_unstable();
+        direct
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_graph() -> (DependencyGraph, [NodeId; 5]) {
+        let mut graph = DependencyGraph::new();
+        let app = graph.add_node("app", "1.0.0");
+        let a = graph.add_node("a", "1.0.0");
+        let b = graph.add_node("b", "1.0.0");
+        let c = graph.add_node("c", "1.0.0");
+        let d = graph.add_node("d", "1.0.0");
+        graph.mark_root(app);
+        graph.add_edge(app, a);
+        graph.add_edge(app, b);
+        graph.add_edge(a, c);
+        graph.add_edge(b, c);
+        graph.add_edge(c, d);
+        // cycles must not hang
+        graph.add_edge(d, c);
+        graph.mark_direct_dependencies_of_roots();
+        (graph, [app, a, b, c, d])
+    }
+
+    #[test]
+    fn nodes_and_edges_are_unique() {
+        let mut graph = DependencyGraph::new();
+        let a = graph.add_node("a", "1.0.0");
+        let b = graph.add_node("b", "1.0.0");
+        assert_eq!(graph.add_node("a", "1.0.0"), a);
+        assert_ne!(graph.add_node("a", "2.0.0"), a);
+        graph.add_edge(a, b);
+        graph.add_edge(a, b);
+        graph.add_edge(a, a);
+        graph.add_edge(a, 42);
+        assert_eq!(graph.edges().len(), 1);
+        assert_eq!(graph.dependencies_of(a).collect::<Vec<_>>(), vec![b]);
+        assert_eq!(graph.dependents_of(b).collect::<Vec<_>>(), vec![a]);
+        assert_eq!(graph.dependents_of(42).count(), 0);
+        assert_eq!(graph.find("b", "1.0.0"), Some(b));
+        assert_eq!(graph.find("b", "2.0.0"), None);
+    }
+
+    #[test]
+    fn roots_and_direct_dependencies_are_marked() {
+        let (graph, [app, a, b, c, _]) = build_graph();
+        assert_eq!(graph.roots().collect::<Vec<_>>(), vec![app]);
+        assert_eq!(graph.direct_dependencies().collect::<Vec<_>>(), vec![a, b]);
+        assert!(!graph.node(c).unwrap().is_direct);
+    }
+
+    #[test]
+    fn transitive_dependencies_know_who_introduced_them() {
+        let (graph, [app, a, b, c, d]) = build_graph();
+        assert_eq!(graph.introduced_by(d), vec![a, b]);
+        assert_eq!(graph.introduced_by(c), vec![a, b]);
+        assert!(graph.introduced_by(a).is_empty());
+        assert!(graph.introduced_by(app).is_empty());
+    }
+}
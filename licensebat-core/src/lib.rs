@@ -8,6 +8,8 @@
 //!
 //! In our case, this crate exposes the [`FileCollector`] trait which will use dependency manifest files (such as `package-lock.json`, `yarn.lock`, `Cargo.lock` or `pubspec.yaml`) to extract information about the different dependencies (see [`Dependency`]) and return a stream of [`RetrievedDependency`] or [`RetrievedDependencyStreamResult`].
 //!
+//! A [`FileCollector`] can also build the [`DependencyGraph`] of the project from the edges declared in the dependency file, so we can know which direct dependency introduced a transitive one.
+//!
 //! For the moment, [`FileCollector`] is the only used trait in all language implementations but this can easily change. For instance, for the Rust language, it would be nice to use [`cargo-metadata`] instead of having to parse the `Cargo.lock` file. That would make it cheaper to get information about some dependencies as we wouldn't need to use any APIs to fetch license information.
 //!
 //! Anyway, having those two different approaches would be still valuable because there are scenarios where we don't have access to all the codebase. When using the CLI, through [licensebat-cli], it makes sense to avoid making http requests at all cost, but this is not possible in [Licensebat's GitHub Service](https://github.com/marketplace/licensebat) as we don't have access to all the codebase but only a few files.
@@ -26,8 +28,13 @@
 
 pub mod collector;
 mod dependency;
+pub mod graph;
+pub mod license;
 pub mod licrc;
+pub mod purl;
 
 #[doc(inline)]
-pub use collector::{Collector, FileCollector};
+pub use collector::{Collector, CollectorRegistry, FileCollector};
 pub use dependency::*;
+#[doc(inline)]
+pub use graph::DependencyGraph;
@@ -0,0 +1,270 @@
+use super::Term;
+use serde::{Deserialize, Serialize};
+use spdx::LicenseItem;
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// Family of a license.
+///
+/// Variants are sorted from the most restrictive to the most permissive one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Category {
+    /// Licenses that don't grant the rights to use, modify or distribute the software (i.e. commercial licenses).
+    Proprietary,
+    /// Copyleft licenses whose obligations are also triggered by network use (i.e. `AGPL-3.0-only`).
+    NetworkCopyleft,
+    /// Copyleft licenses that extend to the whole derived work (i.e. `GPL-3.0-only`).
+    StrongCopyleft,
+    /// Copyleft licenses limited to the files or the library itself (i.e. `LGPL-2.1-only` or `MPL-2.0`).
+    WeakCopyleft,
+    /// Licenses with minimal requirements, usually attribution (i.e. `MIT` or `Apache-2.0`).
+    Permissive,
+    /// Public domain dedications and equivalents (i.e. `CC0-1.0` or `Unlicense`).
+    PublicDomain,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Proprietary => "proprietary",
+            Self::NetworkCopyleft => "network-copyleft",
+            Self::StrongCopyleft => "strong-copyleft",
+            Self::WeakCopyleft => "weak-copyleft",
+            Self::Permissive => "permissive",
+            Self::PublicDomain => "public-domain",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Built-in classification of the SPDX licenses.
+///
+/// Entries ending with `*` match any license starting with that prefix.
+/// The first matching entry wins, so more specific entries must come first.
+const CLASSIFICATION: &[(&str, Category)] = &[
+    // public domain
+    ("0BSD", Category::PublicDomain),
+    ("CC-PDDC", Category::PublicDomain),
+    ("CC0-1.0", Category::PublicDomain),
+    ("PDDL-1.0", Category::PublicDomain),
+    ("SAX-PD*", Category::PublicDomain),
+    ("Unlicense", Category::PublicDomain),
+    ("blessing", Category::PublicDomain),
+    // network copyleft
+    ("AGPL-*", Category::NetworkCopyleft),
+    ("CPAL-1.0", Category::NetworkCopyleft),
+    ("OSL-*", Category::NetworkCopyleft),
+    ("RPL-*", Category::NetworkCopyleft),
+    ("SSPL-1.0", Category::NetworkCopyleft),
+    // weak copyleft (before GPL so LGPL is not shadowed)
+    ("LGPL-*", Category::WeakCopyleft),
+    ("LGPLLR", Category::WeakCopyleft),
+    ("CDDL-*", Category::WeakCopyleft),
+    ("APSL-*", Category::WeakCopyleft),
+    ("CECILL-C", Category::WeakCopyleft),
+    ("CPL-1.0", Category::WeakCopyleft),
+    ("eCos-2.0", Category::WeakCopyleft),
+    ("EPL-*", Category::WeakCopyleft),
+    ("ErlPL-1.1", Category::WeakCopyleft),
+    ("IPL-1.0", Category::WeakCopyleft),
+    ("MPL-*", Category::WeakCopyleft),
+    ("MS-RL", Category::WeakCopyleft),
+    ("OFL-*", Category::WeakCopyleft),
+    ("SPL-1.0", Category::WeakCopyleft),
+    ("Watcom-1.0", Category::WeakCopyleft),
+    // strong copyleft
+    ("GPL-*", Category::StrongCopyleft),
+    ("CC-BY-SA-*", Category::StrongCopyleft),
+    ("CECILL-1.0", Category::StrongCopyleft),
+    ("CECILL-1.1", Category::StrongCopyleft),
+    ("CECILL-2.0", Category::StrongCopyleft),
+    ("CECILL-2.1", Category::StrongCopyleft),
+    ("EUPL-*", Category::StrongCopyleft),
+    ("QPL-1.0", Category::StrongCopyleft),
+    ("RPSL-1.0", Category::StrongCopyleft),
+    ("SimPL-2.0", Category::StrongCopyleft),
+    ("Sleepycat", Category::StrongCopyleft),
+    // non commercial or non free licenses
+    ("BUSL-1.1", Category::Proprietary),
+    ("CC-BY-NC-*", Category::Proprietary),
+    ("CC-BY-NC-SA-*", Category::Proprietary),
+    ("CC-BY-ND-*", Category::Proprietary),
+    ("Elastic-2.0", Category::Proprietary),
+    ("PolyForm-*", Category::Proprietary),
+    // permissive
+    ("AFL-*", Category::Permissive),
+    ("Apache-*", Category::Permissive),
+    ("Artistic-2.0", Category::Permissive),
+    ("BSD-*", Category::Permissive),
+    ("BSL-1.0", Category::Permissive),
+    ("CECILL-B", Category::Permissive),
+    ("CC-BY-*", Category::Permissive),
+    ("ISC", Category::Permissive),
+    ("MIT*", Category::Permissive),
+    ("MS-PL", Category::Permissive),
+    ("NCSA", Category::Permissive),
+    ("PHP-*", Category::Permissive),
+    ("PSF-2.0", Category::Permissive),
+    ("Python-2.0*", Category::Permissive),
+    ("Unicode-*", Category::Permissive),
+    ("UPL-1.0", Category::Permissive),
+    ("W3C*", Category::Permissive),
+    ("WTFPL", Category::Permissive),
+    ("X11*", Category::Permissive),
+    ("Zlib", Category::Permissive),
+    ("zlib-acknowledgement", Category::Permissive),
+];
+
+/// Classifies licenses into [`Category`]s.
+///
+/// It uses the user defined classification first and then the built-in table.
+/// Licenses found in neither are not classified: being OSI or FSF approved says nothing about their family.
+#[derive(Debug, Clone, Default)]
+pub struct Classifier {
+    custom: Arc<BTreeMap<String, Category>>,
+}
+
+impl Classifier {
+    /// Creates a new [`Classifier`] with some user defined classifications.
+    /// Keys are license identifiers (i.e. `LicenseRef-Acme`) and they take precedence over the built-in table.
+    #[must_use]
+    pub fn new(custom: &BTreeMap<String, Category>) -> Self {
+        Self {
+            custom: Arc::new(custom.clone()),
+        }
+    }
+
+    /// Returns the [`Category`] of a [`Term`], if known.
+    /// Exceptions are not taken into account.
+    #[must_use]
+    pub fn classify(&self, term: &Term) -> Option<Category> {
+        match term {
+            Term::Spdx(req) => match &req.license {
+                LicenseItem::Spdx { id, .. } => self
+                    .custom
+                    .get(id.name)
+                    .copied()
+                    .or_else(|| builtin(id.name)),
+                LicenseItem::Other { .. } => self.custom.get(&req.license.to_string()).copied(),
+            },
+            Term::Raw(text) => self.custom.get(*text).copied(),
+        }
+    }
+}
+
+/// Looks for a license in the built-in classification table.
+fn builtin(id: &str) -> Option<Category> {
+    CLASSIFICATION
+        .iter()
+        .find(|(pattern, _)| {
+            pattern
+                .strip_suffix('*')
+                .map_or(*pattern == id, |prefix| id.starts_with(prefix))
+        })
+        .map(|(_, category)| *category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license::Expression;
+
+    fn classify(license: &str, classifier: &Classifier) -> Option<Category> {
+        let mut category = None;
+        Expression::parse(license).evaluate(|term| {
+            category = classifier.classify(term);
+            true
+        });
+        category
+    }
+
+    #[test]
+    fn spdx_licenses_are_classified() {
+        let classifier = Classifier::default();
+        assert_eq!(classify("MIT", &classifier), Some(Category::Permissive));
+        assert_eq!(
+            classify("BSD-3-Clause-Clear", &classifier),
+            Some(Category::Permissive)
+        );
+        assert_eq!(
+            classify("CC0-1.0", &classifier),
+            Some(Category::PublicDomain)
+        );
+        assert_eq!(
+            classify("MPL-2.0", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("LGPL-2.1-or-later", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("GPL-3.0-only", &classifier),
+            Some(Category::StrongCopyleft)
+        );
+        assert_eq!(
+            classify("AGPL-3.0-or-later", &classifier),
+            Some(Category::NetworkCopyleft)
+        );
+        assert_eq!(classify("MSC", &classifier), None);
+    }
+
+    #[test]
+    fn unlisted_licenses_are_not_classified() {
+        let classifier = Classifier::default();
+        // valid SPDX licenses that are not in the built-in table
+        assert_eq!(classify("NTP", &classifier), None);
+        assert_eq!(classify("Zed", &classifier), None);
+    }
+
+    #[test]
+    fn copyleft_licenses_are_not_classified_as_permissive() {
+        let classifier = Classifier::default();
+        assert_eq!(
+            classify("SimPL-2.0", &classifier),
+            Some(Category::StrongCopyleft)
+        );
+        assert_eq!(
+            classify("APSL-2.0", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("Watcom-1.0", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("eCos-2.0", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("CECILL-2.1", &classifier),
+            Some(Category::StrongCopyleft)
+        );
+        assert_eq!(
+            classify("CECILL-C", &classifier),
+            Some(Category::WeakCopyleft)
+        );
+        assert_eq!(
+            classify("CECILL-B", &classifier),
+            Some(Category::Permissive)
+        );
+    }
+
+    #[test]
+    fn user_classification_takes_precedence() {
+        let classifier = Classifier::new(&BTreeMap::from([
+            ("LicenseRef-Acme".to_owned(), Category::Proprietary),
+            ("MPL-2.0".to_owned(), Category::StrongCopyleft),
+            ("MSC".to_owned(), Category::Permissive),
+        ]));
+        assert_eq!(
+            classify("LicenseRef-Acme", &classifier),
+            Some(Category::Proprietary)
+        );
+        assert_eq!(
+            classify("MPL-2.0", &classifier),
+            Some(Category::StrongCopyleft)
+        );
+        assert_eq!(classify("MSC", &classifier), Some(Category::Permissive));
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Expression, Term};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The licenses declared by a dependency, as retrieved from its source.
+///
+/// Every retriever converts the information it gets into this type,
+/// making explicit whether several declarations must all be satisfied or just one of them.
+///
+/// Each declaration is kept as found in the source (after normalization),
+/// so it can be displayed as is. A declaration can itself be an SPDX expression and it will be evaluated as an [`Expression`].
+///
+/// # Example
+///
+/// ```
+/// use licensebat_core::license::LicenseExpression;
+///
+/// // npm's legacy `licenses` array means "any of"
+/// let expression = LicenseExpression::any_of(["MIT", "GPL-3.0-only"]).unwrap();
+///
+/// assert_eq!(expression.licenses(), vec!["MIT", "GPL-3.0-only"]);
+/// assert_eq!(expression.to_string(), "MIT OR GPL-3.0-only");
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case", try_from = "RawLicenseExpression")]
+pub enum LicenseExpression {
+    /// A single license declaration (i.e. `MIT` or `MIT OR Apache-2.0`).
+    License(String),
+    /// Satisfied if any of the expressions is satisfied.
+    AnyOf(Vec<LicenseExpression>),
+    /// Satisfied only if all the expressions are satisfied.
+    AllOf(Vec<LicenseExpression>),
+}
+
+/// Same shape as [`LicenseExpression`], used to reject empty compounds when deserializing.
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RawLicenseExpression {
+    License(String),
+    AnyOf(Vec<LicenseExpression>),
+    AllOf(Vec<LicenseExpression>),
+}
+
+impl TryFrom<RawLicenseExpression> for LicenseExpression {
+    type Error = &'static str;
+
+    fn try_from(raw: RawLicenseExpression) -> Result<Self, Self::Error> {
+        match raw {
+            RawLicenseExpression::License(license) => Ok(Self::License(license)),
+            RawLicenseExpression::AnyOf(expressions) if !expressions.is_empty() => {
+                Ok(Self::AnyOf(expressions))
+            }
+            RawLicenseExpression::AllOf(expressions) if !expressions.is_empty() => {
+                Ok(Self::AllOf(expressions))
+            }
+            _ => Err("a compound license expression must have at least one expression"),
+        }
+    }
+}
+
+impl LicenseExpression {
+    /// Creates a [`LicenseExpression`] with a single license declaration.
+    pub fn license(license: impl Into<String>) -> Self {
+        Self::License(license.into())
+    }
+
+    /// Creates a [`LicenseExpression`] satisfied by any of the given declarations.
+    ///
+    /// It will be `None` if there are no declarations. A single declaration won't be wrapped.
+    pub fn any_of<I, S>(licenses: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::compound(licenses, Self::AnyOf)
+    }
+
+    /// Creates a [`LicenseExpression`] satisfied only if all the given declarations are.
+    ///
+    /// It will be `None` if there are no declarations. A single declaration won't be wrapped.
+    pub fn all_of<I, S>(licenses: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::compound(licenses, Self::AllOf)
+    }
+
+    fn compound<I, S>(licenses: I, wrap: fn(Vec<Self>) -> Self) -> Option<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut licenses = licenses.into_iter().map(Self::license).collect::<Vec<_>>();
+        match licenses.len() {
+            0 => None,
+            1 => licenses.pop(),
+            _ => Some(wrap(licenses)),
+        }
+    }
+
+    /// Gets all the license declarations, in order.
+    #[must_use]
+    pub fn licenses(&self) -> Vec<&str> {
+        match self {
+            Self::License(license) => vec![license.as_str()],
+            Self::AnyOf(expressions) | Self::AllOf(expressions) => {
+                expressions.iter().flat_map(Self::licenses).collect()
+            }
+        }
+    }
+
+    /// Applies a function to every license declaration, i.e. to normalize them.
+    pub fn for_each_license_mut(&mut self, f: &mut impl FnMut(&mut String)) {
+        match self {
+            Self::License(license) => f(license),
+            Self::AnyOf(expressions) | Self::AllOf(expressions) => {
+                for expression in expressions {
+                    expression.for_each_license_mut(f);
+                }
+            }
+        }
+    }
+
+    /// Resolves the expression to a single value, like [`Expression::resolve`] does.
+    ///
+    /// `AnyOf` picks the greatest value and `AllOf` the lowest one.
+    pub fn resolve<T: Ord>(&self, mut value: impl FnMut(&Term) -> T) -> T {
+        self.resolve_with(&mut value)
+    }
+
+    fn resolve_with<T: Ord>(&self, value: &mut impl FnMut(&Term) -> T) -> T {
+        match self {
+            Self::License(license) => Expression::parse(license).resolve(&mut *value),
+            Self::AnyOf(expressions) => expressions
+                .iter()
+                .map(|expression| expression.resolve_with(value))
+                .max()
+                .expect("a compound license expression has at least one expression"),
+            Self::AllOf(expressions) => expressions
+                .iter()
+                .map(|expression| expression.resolve_with(value))
+                .min()
+                .expect("a compound license expression has at least one expression"),
+        }
+    }
+}
+
+impl fmt::Display for LicenseExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (expressions, operator) = match self {
+            Self::License(license) => return f.write_str(license),
+            Self::AnyOf(expressions) => (expressions, " OR "),
+            Self::AllOf(expressions) => (expressions, " AND "),
+        };
+        for (index, expression) in expressions.iter().enumerate() {
+            if index > 0 {
+                f.write_str(operator)?;
+            }
+            let needs_parens = match expression {
+                Self::License(license) => license.contains(char::is_whitespace),
+                _ => true,
+            };
+            if needs_parens {
+                write!(f, "({expression})")?;
+            } else {
+                write!(f, "{expression}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(term: &Term) -> u8 {
+        match term.to_string().as_str() {
+            "MIT" => 3,
+            "Apache-2.0" => 2,
+            _ => 1,
+        }
+    }
+
+    #[test]
+    fn compounds_pick_the_best_or_the_worst_choice() {
+        let any_of = LicenseExpression::any_of(["GPL-3.0-only", "MIT"]).unwrap();
+        let all_of = LicenseExpression::all_of(["GPL-3.0-only", "MIT"]).unwrap();
+        assert_eq!(any_of.resolve(rank), 3);
+        assert_eq!(all_of.resolve(rank), 1);
+        assert_eq!(
+            LicenseExpression::AllOf(vec![
+                LicenseExpression::license("MIT OR GPL-3.0-only"),
+                LicenseExpression::license("Apache-2.0"),
+            ])
+            .resolve(rank),
+            2
+        );
+    }
+
+    #[test]
+    fn single_declarations_are_not_wrapped() {
+        assert_eq!(LicenseExpression::any_of(Vec::<String>::new()), None);
+        assert_eq!(
+            LicenseExpression::all_of(["MIT"]),
+            Some(LicenseExpression::license("MIT"))
+        );
+    }
+
+    #[test]
+    fn it_is_displayed_as_an_expression() {
+        let expression = LicenseExpression::AllOf(vec![
+            LicenseExpression::license("MIT OR Apache-2.0"),
+            LicenseExpression::any_of(["ISC", "BSD-3-Clause"]).unwrap(),
+            LicenseExpression::license("Zlib"),
+        ]);
+        assert_eq!(
+            expression.to_string(),
+            "(MIT OR Apache-2.0) AND (ISC OR BSD-3-Clause) AND Zlib"
+        );
+        assert_eq!(
+            expression.licenses(),
+            vec!["MIT OR Apache-2.0", "ISC", "BSD-3-Clause", "Zlib"]
+        );
+    }
+
+    #[test]
+    fn empty_compounds_are_not_deserialized() {
+        assert!(serde_json::from_str::<LicenseExpression>(r#"{"any_of":[]}"#).is_err());
+        assert!(
+            serde_json::from_str::<LicenseExpression>(r#"{"all_of":[{"any_of":[]}]}"#).is_err()
+        );
+        assert_eq!(
+            serde_json::from_str::<LicenseExpression>(r#"{"any_of":[{"license":"MIT"}]}"#).unwrap(),
+            LicenseExpression::AnyOf(vec![LicenseExpression::license("MIT")])
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use spdx::{
+    expression::{ExprNode, Operator},
+    LicenseItem, LicenseReq, ParseMode,
+};
+use std::fmt;
+
+/// Parse mode used for license expressions.
+///
+/// It follows the SPDX spec but it also accepts lower case operators and the `+` suffix on GNU licenses,
+/// as both of them are quite common in the wild.
+const PARSE_MODE: ParseMode = ParseMode {
+    allow_lower_case_operators: true,
+    allow_slash_as_or_operator: false,
+    allow_imprecise_license_names: false,
+    allow_postfix_plus_on_gpl: true,
+};
+
+/// A license expression declared by a dependency (i.e. `MIT OR Apache-2.0`).
+///
+/// If the declaration is a valid SPDX expression, it will be evaluated term by term:
+///
+/// - `OR` is satisfied if any of its terms is satisfied.
+/// - `AND` is satisfied only if all its terms are satisfied.
+/// - `WITH` exceptions and parentheses are supported.
+/// - `+` and `-or-later` suffixes are satisfied by the same license or any later version of it.
+///
+/// Otherwise, the whole declaration will be treated as a single opaque term.
+#[derive(Clone)]
+pub struct Expression {
+    original: String,
+    spdx: Option<spdx::Expression>,
+}
+
+impl Expression {
+    /// Parses a license declaration.
+    ///
+    /// This never fails. Declarations that are not valid SPDX expressions are kept as a single raw term.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let original = text.trim().to_owned();
+        let spdx = spdx::Expression::parse_mode(&original, PARSE_MODE).ok();
+        Self { original, spdx }
+    }
+
+    /// Returns true if the declaration is a valid SPDX expression.
+    #[must_use]
+    pub const fn is_spdx(&self) -> bool {
+        self.spdx.is_some()
+    }
+
+    /// Returns the original declaration.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.original
+    }
+
+    /// Gets the canonical SPDX form of the expression (i.e. `MIT OR GPL-2.0-or-later` for `MIT or GPL-2.0+`),
+    /// as described in [`Term::canonical`].
+    ///
+    /// It will be `None` if the declaration is not a valid SPDX expression.
+    #[must_use]
+    pub fn canonical(&self) -> Option<String> {
+        let expression = self.spdx.as_ref()?;
+        // the nodes come in postfix order, every operand keeps the operator that built it
+        let mut stack: Vec<(String, Option<Operator>)> = Vec::new();
+        for node in expression.iter() {
+            match node {
+                ExprNode::Req(req) => stack.push((Term::Spdx(&req.req).canonical(), None)),
+                ExprNode::Op(op) => {
+                    // a valid expression always has two operands for each operator
+                    let (Some(right), Some(left)) = (stack.pop(), stack.pop()) else {
+                        unreachable!("invalid SPDX expression");
+                    };
+                    // AND takes precedence over OR
+                    let operand = |(text, operator): (String, Option<Operator>)| {
+                        if *op == Operator::And && operator == Some(Operator::Or) {
+                            format!("({text})")
+                        } else {
+                            text
+                        }
+                    };
+                    let keyword = match op {
+                        Operator::And => "AND",
+                        Operator::Or => "OR",
+                    };
+                    let text = format!("{} {keyword} {}", operand(left), operand(right));
+                    stack.push((text, Some(*op)));
+                }
+            }
+        }
+        stack.pop().map(|(text, _)| text)
+    }
+
+    /// Gets the terms of the expression, in order.
+    ///
+    /// A declaration that is not a valid SPDX expression is a single raw term.
+    #[must_use]
+    pub fn terms(&self) -> Vec<Term<'_>> {
+        self.spdx.as_ref().map_or_else(
+            || vec![Term::Raw(&self.original)],
+            |expression| {
+                expression
+                    .requirements()
+                    .map(|req| Term::Spdx(&req.req))
+                    .collect()
+            },
+        )
+    }
+
+    /// Evaluates the expression.
+    ///
+    /// The given function decides whether a single [`Term`] is allowed or not
+    /// and the result is combined according to the operators of the expression.
+    pub fn evaluate(&self, allow: impl FnMut(&Term) -> bool) -> bool {
+        self.resolve(allow)
+    }
+
+    /// Resolves the expression to a single value.
+    ///
+    /// The given function computes an ordered value for every [`Term`] and the operators pick among them:
+    /// `OR` keeps the greatest value (the best choice) while `AND` keeps the lowest one (the most restrictive term).
+    pub fn resolve<T: Ord>(&self, mut value: impl FnMut(&Term) -> T) -> T {
+        let Some(expression) = &self.spdx else {
+            return value(&Term::Raw(&self.original));
+        };
+        // the nodes come in postfix order
+        let mut stack = Vec::new();
+        for node in expression.iter() {
+            match node {
+                ExprNode::Req(req) => stack.push(value(&Term::Spdx(&req.req))),
+                ExprNode::Op(op) => {
+                    // a valid expression always has two operands for each operator
+                    let (Some(right), Some(left)) = (stack.pop(), stack.pop()) else {
+                        unreachable!("invalid SPDX expression");
+                    };
+                    stack.push(match op {
+                        Operator::And => left.min(right),
+                        Operator::Or => left.max(right),
+                    });
+                }
+            }
+        }
+        stack
+            .pop()
+            .expect("an SPDX expression has at least one term")
+    }
+}
+
+impl fmt::Debug for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Expression")
+            .field("original", &self.original)
+            .field("is_spdx", &self.is_spdx())
+            .finish()
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+
+/// A single term of an [`Expression`].
+#[derive(Debug, Clone, Copy)]
+pub enum Term<'a> {
+    /// A valid SPDX license requirement, optionally with an exception.
+    Spdx(&'a LicenseReq),
+    /// A declaration that couldn't be understood as an SPDX expression.
+    Raw(&'a str),
+}
+
+impl Term<'_> {
+    /// Gets the canonical SPDX form of the term, i.e. `GPL-2.0-or-later` for `GPL-2.0+` and `GPL-2.0-only` for the deprecated `GPL-2.0`.
+    ///
+    /// Raw terms are kept as they are.
+    #[must_use]
+    pub fn canonical(&self) -> String {
+        let Self::Spdx(req) = self else {
+            return self.to_string();
+        };
+        let license = match &req.license {
+            LicenseItem::Spdx { id, or_later } if id.is_gnu() => {
+                let suffix = if *or_later { "-or-later" } else { "-only" };
+                spdx::license_id(&format!("{}{suffix}", id.name))
+                    .map_or_else(|| req.license.to_string(), |id| id.name.to_owned())
+            }
+            license => license.to_string(),
+        };
+        match &req.exception {
+            Some(exception) => format!("{license} WITH {}", exception.name),
+            None => license,
+        }
+    }
+}
+
+impl fmt::Display for Term<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spdx(req) => req.fmt(f),
+            Self::Raw(text) => f.write_str(text),
+        }
+    }
+}
+
+/// A license declared in the `.licrc` file which can be matched against the [`Term`]s of an [`Expression`].
+#[derive(Debug, Clone)]
+pub struct LicenseMatcher {
+    original: String,
+    req: Option<LicenseReq>,
+}
+
+impl LicenseMatcher {
+    /// Parses a license declared in the `.licrc` file.
+    ///
+    /// Licenses that are not valid SPDX identifiers will only match terms with exactly the same text.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let original = text.trim().to_owned();
+        let req = spdx::Expression::parse_mode(&original, PARSE_MODE)
+            .ok()
+            .and_then(|expression| {
+                let mut nodes = expression.iter();
+                match (nodes.next(), nodes.next()) {
+                    (Some(spdx::expression::ExprNode::Req(req)), None) => Some(req.req.clone()),
+                    _ => None,
+                }
+            });
+        Self { original, req }
+    }
+
+    /// Returns true if the term is covered by this license.
+    ///
+    /// - An exception in the term is covered if this license has no exception or the same one.
+    /// - A `+` term (i.e. `GPL-2.0+`) is covered by the same license or any later version of it.
+    /// - A `+` license (i.e. `MPL-1.1+`) covers the same license or any later version of it.
+    #[must_use]
+    pub fn matches(&self, term: &Term) -> bool {
+        self.compare(term, item_matches)
+    }
+
+    /// Returns true if every license the term can be used under is covered by this license.
+    ///
+    /// It's the check used by deny and warn lists: a `+` term (i.e. `GPL-2.0+`) is only covered by
+    /// a `+` license of the same or an earlier version (i.e. `GPL-2.0-or-later`), as it can still be used under the earlier versions.
+    /// Exceptions work like in [`LicenseMatcher::matches`].
+    #[must_use]
+    pub fn includes(&self, term: &Term) -> bool {
+        self.compare(term, item_includes)
+    }
+
+    fn compare(&self, term: &Term, items: fn(&LicenseItem, &LicenseItem) -> bool) -> bool {
+        match (&self.req, term) {
+            (Some(license), Term::Spdx(req)) => {
+                let exception_matches =
+                    license.exception.is_none() || license.exception == req.exception;
+                exception_matches && items(&license.license, &req.license)
+            }
+            (_, term) => self.original == term.to_string(),
+        }
+    }
+}
+
+/// Checks if a license item of the `.licrc` file covers a license item of a dependency.
+fn item_matches(license: &LicenseItem, required: &LicenseItem) -> bool {
+    match (license, required) {
+        (
+            LicenseItem::Spdx {
+                id: license_id,
+                or_later: license_or_later,
+            },
+            LicenseItem::Spdx {
+                id: required_id,
+                or_later: required_or_later,
+            },
+        ) => {
+            license_id == required_id
+                || (*required_or_later && is_same_or_later(license_id.name, required_id.name))
+                || (*license_or_later && is_same_or_later(required_id.name, license_id.name))
+        }
+        (LicenseItem::Other { .. }, LicenseItem::Other { .. }) => license == required,
+        _ => false,
+    }
+}
+
+/// Checks if a license item of the `.licrc` file covers every version allowed by a license item of a dependency.
+fn item_includes(license: &LicenseItem, required: &LicenseItem) -> bool {
+    match (license, required) {
+        (
+            LicenseItem::Spdx {
+                id: license_id,
+                or_later: license_or_later,
+            },
+            LicenseItem::Spdx {
+                id: required_id,
+                or_later: required_or_later,
+            },
+        ) => {
+            if license_id == required_id {
+                *license_or_later || !*required_or_later
+            } else {
+                *license_or_later && is_same_or_later(required_id.name, license_id.name)
+            }
+        }
+        (LicenseItem::Other { .. }, LicenseItem::Other { .. }) => license == required,
+        _ => false,
+    }
+}
+
+/// Returns true if `candidate` belongs to the same license family as `base` (i.e. `GPL`)
+/// and its version is greater or equal.
+fn is_same_or_later(candidate: &str, base: &str) -> bool {
+    match (split_version(candidate), split_version(base)) {
+        (Some((candidate_family, candidate_version)), Some((base_family, base_version))) => {
+            candidate_family == base_family && candidate_version >= base_version
+        }
+        _ => false,
+    }
+}
+
+/// Splits a license identifier like `LGPL-2.1` into its family and its numeric version.
+fn split_version(id: &str) -> Option<(&str, Vec<u32>)> {
+    let (family, version) = id.rsplit_once('-')?;
+    let version = version
+        .split('.')
+        .map(str::parse)
+        .collect::<Result<Vec<u32>, _>>()
+        .ok()?;
+    Some((family, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_accepted(expression: &str, accepted: &[&str]) -> bool {
+        let matchers = accepted
+            .iter()
+            .map(|l| LicenseMatcher::parse(l))
+            .collect::<Vec<_>>();
+        Expression::parse(expression).evaluate(|term| matchers.iter().any(|m| m.matches(term)))
+    }
+
+    #[test]
+    fn or_is_satisfied_by_any_term() {
+        assert!(is_accepted("MIT OR Apache-2.0", &["MIT"]));
+        assert!(is_accepted("MIT OR Apache-2.0", &["Apache-2.0"]));
+        assert!(!is_accepted("MIT OR Apache-2.0", &["ISC"]));
+    }
+
+    #[test]
+    fn and_needs_every_term() {
+        assert!(!is_accepted("MIT AND Apache-2.0", &["MIT"]));
+        assert!(is_accepted("MIT AND Apache-2.0", &["MIT", "Apache-2.0"]));
+    }
+
+    #[test]
+    fn parentheses_and_lower_case_operators_are_supported() {
+        assert!(is_accepted("(MIT or ISC) and Zlib", &["ISC", "Zlib"]));
+        assert!(!is_accepted("(MIT OR ISC) AND Zlib", &["ISC"]));
+        assert!(is_accepted("(MIT OR GPL-2.0)", &["MIT"]));
+    }
+
+    #[test]
+    fn exceptions_are_covered_by_the_base_license() {
+        let expression = "Apache-2.0 WITH LLVM-exception";
+        assert!(is_accepted(expression, &["Apache-2.0"]));
+        assert!(is_accepted(expression, &["Apache-2.0 WITH LLVM-exception"]));
+        assert!(!is_accepted(
+            "GPL-2.0-only WITH Classpath-exception-2.0",
+            &["GPL-2.0-only WITH Autoconf-exception-2.0"]
+        ));
+    }
+
+    #[test]
+    fn or_later_is_satisfied_by_later_versions() {
+        assert!(is_accepted("GPL-2.0-or-later", &["GPL-3.0-only"]));
+        assert!(is_accepted("GPL-2.0+", &["GPL-2.0-only"]));
+        assert!(is_accepted("MPL-1.1+", &["MPL-2.0"]));
+        assert!(!is_accepted("GPL-3.0-or-later", &["GPL-2.0-only"]));
+        assert!(!is_accepted("GPL-2.0-or-later", &["LGPL-3.0-only"]));
+        assert!(!is_accepted("GPL-2.0-only", &["GPL-3.0-only"]));
+    }
+
+    #[test]
+    fn or_later_licenses_cover_later_versions() {
+        assert!(is_accepted("GPL-3.0-only", &["GPL-2.0-or-later"]));
+        assert!(!is_accepted("GPL-2.0-only", &["GPL-3.0-or-later"]));
+    }
+
+    #[test]
+    fn or_later_terms_are_only_included_if_every_version_is() {
+        let includes = |expression: &str, license: &str| {
+            let matcher = LicenseMatcher::parse(license);
+            Expression::parse(expression).evaluate(|term| matcher.includes(term))
+        };
+        assert!(!includes("GPL-2.0-or-later", "GPL-3.0-only"));
+        assert!(!includes("GPL-2.0+", "GPL-3.0-only"));
+        assert!(!includes("GPL-2.0+", "GPL-2.0-only"));
+        assert!(!includes("LGPL-2.1+", "LGPL-3.0"));
+        assert!(includes("GPL-3.0+", "GPL-2.0-or-later"));
+        assert!(includes("GPL-2.0-or-later", "GPL-2.0-or-later"));
+        assert!(includes("GPL-3.0-only", "GPL-2.0-or-later"));
+        assert!(includes("GPL-2.0-only", "GPL-2.0"));
+        assert!(!includes("GPL-2.0-only", "GPL-3.0-or-later"));
+        assert!(includes("Apache-2.0 WITH LLVM-exception", "Apache-2.0"));
+        assert!(includes("Apache 2.0", "Apache 2.0"));
+    }
+
+    #[test]
+    fn resolve_picks_the_best_choice_for_or_and_the_worst_for_and() {
+        let rank = |term: &Term| match term.to_string().as_str() {
+            "MIT" => 3,
+            "Apache-2.0" => 2,
+            _ => 1,
+        };
+        assert_eq!(Expression::parse("MIT OR GPL-3.0-only").resolve(rank), 3);
+        assert_eq!(Expression::parse("MIT AND Apache-2.0").resolve(rank), 2);
+        assert_eq!(
+            Expression::parse("(MIT AND Apache-2.0) OR GPL-3.0-only").resolve(rank),
+            2
+        );
+        assert_eq!(Expression::parse("Apache 2.0").resolve(rank), 1);
+    }
+
+    #[test]
+    fn terms_have_a_canonical_form() {
+        let terms = |expression: &str| {
+            Expression::parse(expression)
+                .terms()
+                .iter()
+                .map(Term::canonical)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            terms("MIT or (GPL-2.0+ AND GPL-3.0)"),
+            vec!["MIT", "GPL-2.0-or-later", "GPL-3.0-only"]
+        );
+        assert_eq!(
+            terms("LGPL-2.1-or-later WITH Classpath-exception-2.0 OR MPL-1.1+"),
+            vec!["LGPL-2.1-or-later WITH Classpath-exception-2.0", "MPL-1.1+"]
+        );
+        assert_eq!(terms("Apache 2.0"), vec!["Apache 2.0"]);
+    }
+
+    #[test]
+    fn expressions_have_a_canonical_form() {
+        let canonical = |expression: &str| Expression::parse(expression).canonical();
+        assert_eq!(
+            canonical("MIT or Apache-2.0").as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            canonical("(MIT or GPL-2.0+) and (ISC AND Zlib)").as_deref(),
+            Some("(MIT OR GPL-2.0-or-later) AND ISC AND Zlib")
+        );
+        assert_eq!(
+            canonical("MIT AND ISC OR GPL-3.0").as_deref(),
+            Some("MIT AND ISC OR GPL-3.0-only")
+        );
+        assert_eq!(canonical("Apache 2.0"), None);
+    }
+
+    #[test]
+    fn non_spdx_declarations_are_compared_as_text() {
+        assert!(is_accepted("MSC", &["MSC"]));
+        assert!(is_accepted("Apache 2.0", &["Apache 2.0"]));
+        assert!(!is_accepted("Apache 2.0", &["Apache-2.0"]));
+        assert!(!Expression::parse("Apache 2.0").is_spdx());
+    }
+}
@@ -0,0 +1,25 @@
+//! Types to parse and evaluate license information.
+//!
+//! Dependencies usually declare their licenses as [SPDX license expressions](https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/),
+//! such as `MIT OR Apache-2.0` or `GPL-2.0-or-later WITH Classpath-exception-2.0`.
+//!
+//! The [`Expression`] type parses those declarations and evaluates them against a set of [`LicenseMatcher`]s,
+//! which are built from the licenses declared in the `.licrc` file.
+//!
+//! Licenses can also be grouped by [`Category`] (permissive, weak copyleft, strong copyleft...) using a [`Classifier`],
+//! so policies don't need to list every single license identifier.
+//!
+//! The licenses retrieved for a dependency are kept in a [`LicenseExpression`], which tells whether
+//! several declarations must all be satisfied or just one of them (i.e. npm's legacy `licenses` array).
+//!
+//! As registries are not always strict about the licenses they expose, the [`Normalizer`] maps them to canonical SPDX identifiers before they're validated.
+mod category;
+mod declared;
+mod expression;
+mod normalize;
+
+pub use category::{Category, Classifier};
+pub use declared::LicenseExpression;
+pub use expression::{Expression, LicenseMatcher, Term};
+pub use normalize::Normalizer;
+pub use spdx::LicenseReq;
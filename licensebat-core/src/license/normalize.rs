@@ -0,0 +1,303 @@
+use super::Expression;
+use crate::RetrievedDependency;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Built-in aliases for license identifiers commonly found in the registries.
+///
+/// Keys must be lower case. Names that don't tell the version or the variant of a license
+/// (i.e. `BSD` or `GPL`) are not aliased on purpose: guessing would hide the ambiguity from the policy.
+const ALIASES: &[(&str, &str)] = &[
+    ("agplv3", "AGPL-3.0-only"),
+    ("apache", "Apache-2.0"),
+    ("apache 2", "Apache-2.0"),
+    ("apache 2.0", "Apache-2.0"),
+    ("apache license 2.0", "Apache-2.0"),
+    ("apache license version 2.0", "Apache-2.0"),
+    ("apache license, version 2.0", "Apache-2.0"),
+    ("apache-2", "Apache-2.0"),
+    ("apache2", "Apache-2.0"),
+    ("asl 2.0", "Apache-2.0"),
+    ("bsd 2 clause", "BSD-2-Clause"),
+    ("bsd 2-clause", "BSD-2-Clause"),
+    ("bsd 3 clause", "BSD-3-Clause"),
+    ("bsd 3-clause", "BSD-3-Clause"),
+    ("bsd-2", "BSD-2-Clause"),
+    ("bsd-3", "BSD-3-Clause"),
+    ("bsd2", "BSD-2-Clause"),
+    ("bsd3", "BSD-3-Clause"),
+    ("cc0", "CC0-1.0"),
+    ("eclipse public license 2.0", "EPL-2.0"),
+    ("expat", "MIT"),
+    ("gpl v2", "GPL-2.0-only"),
+    ("gpl v3", "GPL-3.0-only"),
+    ("gplv2", "GPL-2.0-only"),
+    ("gplv3", "GPL-3.0-only"),
+    ("isc license", "ISC"),
+    ("lgplv3", "LGPL-3.0-only"),
+    ("mit license", "MIT"),
+    ("mit/x11", "MIT"),
+    ("mpl", "MPL-2.0"),
+    ("mpl 2.0", "MPL-2.0"),
+    ("mpl2", "MPL-2.0"),
+    ("new bsd", "BSD-3-Clause"),
+    ("simplified bsd", "BSD-2-Clause"),
+    ("the mit license", "MIT"),
+    ("the unlicense", "Unlicense"),
+    ("x11/mit", "MIT"),
+];
+
+/// Maps the license identifiers returned by the registries to canonical SPDX identifiers.
+///
+/// Registries are not always strict about the licenses they expose.
+/// npm may return `Apache 2.0` or `MIT/X11`, pub.dev uses names like `BSD 3 Clause`
+/// and crates.io still exposes the legacy `MIT/Apache-2.0` syntax.
+///
+/// Normalization works like this:
+///
+/// 1. If the whole declaration is a known alias (user aliases first, then the built-in ones), the alias is used.
+/// 2. If the declaration is already a valid SPDX expression, it's kept as is (removing redundant outer parentheses).
+/// 3. Otherwise, `/` is treated as `OR` and every license in the declaration is looked up in the aliases and the SPDX list ignoring case.
+///    If the result is a valid SPDX expression it will be used, if not, the declaration will be kept untouched.
+#[derive(Debug, Clone, Default)]
+pub struct Normalizer {
+    aliases: Arc<BTreeMap<String, String>>,
+}
+
+impl Normalizer {
+    /// Creates a new [`Normalizer`] with some user defined aliases.
+    ///
+    /// User aliases take precedence over the built-in ones and they're case insensitive.
+    #[must_use]
+    pub fn new(aliases: &BTreeMap<String, String>) -> Self {
+        let aliases = aliases
+            .iter()
+            .map(|(alias, license)| (alias.trim().to_lowercase(), license.trim().to_owned()))
+            .collect();
+        Self {
+            aliases: Arc::new(aliases),
+        }
+    }
+
+    /// Normalizes a license declaration.
+    #[must_use]
+    pub fn normalize(&self, license: &str) -> String {
+        let license = license.trim().trim_matches('"').trim();
+
+        if let Some(alias) = self.alias(license) {
+            return alias;
+        }
+
+        if Expression::parse(license).is_spdx() {
+            return strip_outer_parens(license).to_owned();
+        }
+
+        let normalized = tokenize(license)
+            .into_iter()
+            .map(|token| match token {
+                Token::Open => "(".to_owned(),
+                Token::Close => ")".to_owned(),
+                Token::Operator(op) => op.to_owned(),
+                Token::License(words) => self.normalize_license(&words.join(" ")),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace("( ", "(")
+            .replace(" )", ")");
+
+        if Expression::parse(&normalized).is_spdx() {
+            strip_outer_parens(&normalized).to_owned()
+        } else {
+            license.to_owned()
+        }
+    }
+
+    /// Normalizes all the licenses of a [`RetrievedDependency`].
+    #[must_use]
+    pub fn normalize_dependency(&self, mut dependency: RetrievedDependency) -> RetrievedDependency {
+        if let Some(licenses) = dependency.licenses.as_mut() {
+            licenses.for_each_license_mut(&mut |license| *license = self.normalize(license));
+        }
+        dependency
+    }
+
+    /// Normalizes a single license identifier (no operators).
+    fn normalize_license(&self, license: &str) -> String {
+        self.alias(license)
+            .or_else(|| spdx_id(license))
+            .unwrap_or_else(|| license.to_owned())
+    }
+
+    /// Looks for an alias, user aliases first.
+    fn alias(&self, license: &str) -> Option<String> {
+        let key = license.to_lowercase();
+        self.aliases.get(&key).cloned().or_else(|| {
+            ALIASES
+                .binary_search_by(|(alias, _)| (*alias).cmp(key.as_str()))
+                .ok()
+                .map(|index| ALIASES[index].1.to_owned())
+        })
+    }
+}
+
+/// Finds an SPDX license or exception identifier ignoring case.
+/// The `+` suffix is preserved.
+fn spdx_id(license: &str) -> Option<String> {
+    let (id, plus) = license
+        .strip_suffix('+')
+        .map_or((license, ""), |id| (id, "+"));
+    spdx::identifiers::LICENSES
+        .iter()
+        .map(|(name, ..)| *name)
+        .chain(spdx::identifiers::EXCEPTIONS.iter().map(|(name, _)| *name))
+        .find(|name| name.eq_ignore_ascii_case(id))
+        .map(|name| format!("{name}{plus}"))
+}
+
+/// Removes the parentheses wrapping a whole expression, i.e. `(MIT OR Apache-2.0)`.
+fn strip_outer_parens(license: &str) -> &str {
+    let mut current = license;
+    while let Some(inner) = current.strip_prefix('(').and_then(|l| l.strip_suffix(')')) {
+        // make sure the first parenthesis is closed by the last one
+        let mut depth = 0;
+        let wraps_everything = inner.chars().all(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => (),
+            }
+            depth >= 0
+        });
+        if !wraps_everything {
+            break;
+        }
+        current = inner.trim();
+    }
+    current
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    Open,
+    Close,
+    Operator(&'static str),
+    License(Vec<&'a str>),
+}
+
+/// Splits a license declaration into parentheses, operators and licenses.
+/// Consecutive words that are not operators are considered to be a single license (i.e. `BSD 3 Clause`).
+fn tokenize(license: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    for (index, c) in license.char_indices() {
+        if c.is_whitespace() || matches!(c, '(' | ')' | '/') {
+            push_word(&mut tokens, &license[start..index]);
+            match c {
+                '(' => tokens.push(Token::Open),
+                ')' => tokens.push(Token::Close),
+                '/' => tokens.push(Token::Operator("OR")),
+                _ => (),
+            }
+            start = index + c.len_utf8();
+        }
+    }
+    push_word(&mut tokens, &license[start..]);
+    tokens
+}
+
+/// Adds a word to the tokens, joining it to the previous license if needed.
+fn push_word<'a>(tokens: &mut Vec<Token<'a>>, word: &'a str) {
+    if word.is_empty() {
+        return;
+    }
+    let operator = match word.to_uppercase().as_str() {
+        "OR" => Some("OR"),
+        "AND" => Some("AND"),
+        "WITH" => Some("WITH"),
+        _ => None,
+    };
+    match (operator, tokens.last_mut()) {
+        (Some(op), _) => tokens.push(Token::Operator(op)),
+        (None, Some(Token::License(words))) => words.push(word),
+        (None, _) => tokens.push(Token::License(vec![word])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_aliases_are_sorted() {
+        assert!(ALIASES.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn npm_identifiers_are_normalized() {
+        let normalizer = Normalizer::default();
+        assert_eq!(normalizer.normalize("Apache 2.0"), "Apache-2.0");
+        assert_eq!(normalizer.normalize("MIT/X11"), "MIT");
+        assert_eq!(normalizer.normalize("(MIT OR GPL-2.0)"), "MIT OR GPL-2.0");
+        assert_eq!(normalizer.normalize("\"mit\""), "MIT");
+    }
+
+    #[test]
+    fn pub_dev_identifiers_are_normalized() {
+        let normalizer = Normalizer::default();
+        assert_eq!(normalizer.normalize("BSD 3 Clause"), "BSD-3-Clause");
+        assert_eq!(normalizer.normalize("BSD 2 Clause"), "BSD-2-Clause");
+    }
+
+    #[test]
+    fn legacy_slash_syntax_is_normalized() {
+        let normalizer = Normalizer::default();
+        assert_eq!(normalizer.normalize("MIT/Apache-2.0"), "MIT OR Apache-2.0");
+        assert_eq!(
+            normalizer.normalize("(mit/apache 2.0) and zlib"),
+            "(MIT OR Apache-2.0) AND Zlib"
+        );
+    }
+
+    #[test]
+    fn valid_expressions_are_kept() {
+        let normalizer = Normalizer::default();
+        assert_eq!(
+            normalizer.normalize("(MIT OR ISC) AND (Zlib OR BSD-3-Clause)"),
+            "(MIT OR ISC) AND (Zlib OR BSD-3-Clause)"
+        );
+        assert_eq!(normalizer.normalize("GPL-2.0+"), "GPL-2.0+");
+    }
+
+    #[test]
+    fn unknown_identifiers_are_kept() {
+        let normalizer = Normalizer::default();
+        assert_eq!(
+            normalizer.normalize("SEE LICENSE IN LICENSE.md"),
+            "SEE LICENSE IN LICENSE.md"
+        );
+        assert_eq!(normalizer.normalize("MSC"), "MSC");
+    }
+
+    #[test]
+    fn ambiguous_names_are_kept() {
+        let normalizer = Normalizer::default();
+        assert_eq!(normalizer.normalize("BSD"), "BSD");
+        assert_eq!(normalizer.normalize("GPL"), "GPL");
+        assert_eq!(normalizer.normalize("LGPL"), "LGPL");
+        assert_eq!(normalizer.normalize("AGPL"), "AGPL");
+        assert_eq!(normalizer.normalize("MIT/BSD"), "MIT/BSD");
+    }
+
+    #[test]
+    fn user_aliases_take_precedence() {
+        let aliases = BTreeMap::from([
+            ("BSD".to_owned(), "BSD-3-Clause".to_owned()),
+            ("Acme Commercial".to_owned(), "LicenseRef-Acme".to_owned()),
+        ]);
+        let normalizer = Normalizer::new(&aliases);
+        assert_eq!(normalizer.normalize("bsd"), "BSD-3-Clause");
+        assert_eq!(
+            normalizer.normalize("acme commercial OR MIT"),
+            "LicenseRef-Acme OR MIT"
+        );
+    }
+}
@@ -0,0 +1,568 @@
+use super::{extends, Date, LicRc, LicRcBehavior, LicRcDependencies, LicRcLicenses};
+use crate::license::{Expression, Normalizer};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt, ops::Range, path::PathBuf};
+use toml_edit::{ImDocument, Item, TableLike};
+
+/// Keys allowed at the root of the `.licrc` file.
+const ROOT_KEYS: &[&str] = &["extends", "licenses", "dependencies", "behavior"];
+/// Keys allowed in the `[licenses]` section.
+const LICENSES_KEYS: &[&str] = &[
+    "accepted",
+    "unaccepted",
+    "warned",
+    "aliases",
+    "allowed_categories",
+    "denied_categories",
+    "warned_categories",
+    "categories",
+    "dev",
+    "optional",
+    "transitive",
+];
+/// Sections of the `[licenses]` section with the policies of some scopes (i.e. `[licenses.dev]`).
+const SCOPES: [&str; 3] = ["dev", "optional", "transitive"];
+/// Keys allowed in the policies of the scopes.
+const SCOPE_KEYS: &[&str] = &[
+    "accepted",
+    "unaccepted",
+    "warned",
+    "allowed_categories",
+    "denied_categories",
+    "warned_categories",
+];
+/// Keys allowed in the `[dependencies]` section.
+const DEPENDENCIES_KEYS: &[&str] = &[
+    "ignored",
+    "overrides",
+    "ignore_dev_dependencies",
+    "ignore_optional_dependencies",
+    "ignore_transitive_dependencies",
+];
+/// Keys allowed in the `[behavior]` section.
+const BEHAVIOR_KEYS: &[&str] = &[
+    "run_only_on_dependency_modification",
+    "do_not_block_pr",
+    "retriever_buffer_size",
+    "min_detection_confidence",
+    "do_not_show_ignored_dependencies",
+    "do_not_show_dev_dependencies",
+    "do_not_show_optional_dependencies",
+];
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The `.licrc` file can't be used.
+    Error,
+    /// The `.licrc` file can be used but it probably doesn't do what it's meant to.
+    Warning,
+}
+
+/// A problem found while checking a `.licrc` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Severity of the problem.
+    pub severity: Severity,
+    /// Description of the problem.
+    pub message: String,
+    /// File where the problem has been found, if known.
+    pub file: Option<PathBuf>,
+    /// Position of the problem in the file, if known.
+    pub location: Option<Location>,
+}
+
+/// Position of a [`Diagnostic`] in a `.licrc` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number, starting at 1.
+    pub column: usize,
+    /// Length of the highlighted text.
+    pub length: usize,
+    /// Content of the line.
+    pub source_line: String,
+}
+
+impl Diagnostic {
+    pub(super) fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub(super) fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            file: None,
+            location: None,
+        }
+    }
+
+    /// Returns true if the diagnostic is an error.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub(super) fn in_file(mut self, file: impl Into<PathBuf>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    fn at(mut self, content: &str, span: Option<Range<usize>>) -> Self {
+        self.location = span.map(|span| Location::new(content, span));
+        self
+    }
+}
+
+impl Location {
+    fn new(content: &str, span: Range<usize>) -> Self {
+        // spans of array values may include the leading whitespace
+        let start = span.start.min(content.len());
+        let start = content[start..span.end.clamp(start, content.len())]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(start, |offset| start + offset);
+        let line_start = content[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = content[start..]
+            .find('\n')
+            .map_or(content.len(), |index| start + index);
+        let source_line = content[line_start..line_end].trim_end_matches('\r');
+        Self {
+            line: content[..start].matches('\n').count() + 1,
+            column: content[line_start..start].chars().count() + 1,
+            length: content[start..span.end.clamp(start, line_end)]
+                .chars()
+                .count()
+                .max(1),
+            source_line: source_line.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.message)?;
+        let file = self
+            .file
+            .as_ref()
+            .map_or_else(|| ".licrc".to_owned(), |file| file.display().to_string());
+        match &self.location {
+            Some(location) => {
+                let gutter = " ".repeat(location.line.to_string().len());
+                write!(
+                    f,
+                    "\n{gutter}--> {file}:{}:{}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+                    location.line,
+                    location.column,
+                    location.line,
+                    location.source_line,
+                    " ".repeat(location.column - 1),
+                    "^".repeat(location.length)
+                )
+            }
+            None if self.file.is_some() => write!(f, "\n --> {file}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Checks the content of a single `.licrc` file.
+///
+/// It looks for syntax errors, unknown keys, invalid values, incompatible options
+/// and license identifiers that are not valid SPDX identifiers.
+pub(super) fn check(content: &str) -> Vec<Diagnostic> {
+    let document = match ImDocument::parse(content) {
+        Ok(document) => document,
+        Err(e) => return vec![Diagnostic::error(e.message().trim()).at(content, e.span())],
+    };
+
+    let mut diagnostics = vec![];
+    check_keys(content, document.as_table(), &[], &mut diagnostics);
+
+    if let Some(licenses) = document.get("licenses").and_then(Item::as_table_like) {
+        check_licenses(content, licenses, &mut diagnostics);
+    }
+
+    if let Some(behavior) = document.get("behavior").and_then(Item::as_table_like) {
+        check_behavior(content, behavior, &mut diagnostics);
+    }
+
+    if !diagnostics.iter().any(Diagnostic::is_error) {
+        // look for invalid values, like a string where a list is expected
+        match parse(content) {
+            Ok(licrc) => check_exceptions(content, &document, &licrc, &mut diagnostics),
+            Err(e) => {
+                // parsing the content directly keeps the spans, but it fails with `!` keys
+                let span = toml::from_str::<File>(content)
+                    .err()
+                    .filter(|direct| direct.message() == e.message())
+                    .and_then(|direct| direct.span());
+                diagnostics.push(Diagnostic::error(e.message().trim()).at(content, span));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// A single `.licrc` file, including its `extends` key.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct File {
+    extends: Option<toml::Value>,
+    #[serde(default)]
+    licenses: LicRcLicenses,
+    #[serde(default)]
+    dependencies: LicRcDependencies,
+    #[serde(default)]
+    behavior: LicRcBehavior,
+}
+
+/// Parses a single `.licrc` file ignoring its `extends` key.
+fn parse(content: &str) -> Result<LicRc, toml::de::Error> {
+    let mut table: toml::Table = toml::from_str(content)?;
+    table.remove("extends");
+    // merging into an empty table gets rid of the `!` entries used to remove inherited values
+    let mut resolved = toml::Table::new();
+    extends::merge(&mut resolved, table);
+    resolved.try_into()
+}
+
+/// Returns the keys allowed in a section or `None` if any key is allowed.
+fn known_keys(path: &[&str]) -> Option<&'static [&'static str]> {
+    match path {
+        [] => Some(ROOT_KEYS),
+        ["licenses"] => Some(LICENSES_KEYS),
+        ["licenses", scope] if SCOPES.contains(scope) => Some(SCOPE_KEYS),
+        ["dependencies"] => Some(DEPENDENCIES_KEYS),
+        ["behavior"] => Some(BEHAVIOR_KEYS),
+        _ => None,
+    }
+}
+
+fn check_keys(
+    content: &str,
+    table: &dyn TableLike,
+    path: &[&str],
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(known) = known_keys(path) else {
+        return;
+    };
+    for (name, item) in table.iter() {
+        let key_span = table.get_key_value(name).and_then(|(key, _)| key.span());
+        let key = name.strip_prefix('!').unwrap_or(name);
+        if known.contains(&key) {
+            if let Some(table) = item.as_table_like() {
+                let mut path = path.to_vec();
+                path.push(key);
+                check_keys(content, table, &path, diagnostics);
+            }
+            continue;
+        }
+        let section = if path.is_empty() {
+            "the root of the file".to_owned()
+        } else {
+            format!("`[{}]`", path.join("."))
+        };
+        let mut message = format!("unknown key `{key}` in {section}");
+        if let Some(suggestion) = suggest(key, known) {
+            message.push_str(&format!(", did you mean `{suggestion}`?"));
+        }
+        diagnostics.push(Diagnostic::error(message).at(content, key_span));
+    }
+}
+
+/// Warns about licenses that are not valid SPDX identifiers and checks the policies of every scope.
+fn check_licenses(content: &str, licenses: &dyn TableLike, diagnostics: &mut Vec<Diagnostic>) {
+    let aliases = licenses
+        .get("aliases")
+        .and_then(Item::as_table_like)
+        .map(|aliases| {
+            aliases
+                .iter()
+                .filter_map(|(alias, license)| {
+                    Some((alias.to_owned(), license.as_str()?.to_owned()))
+                })
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let normalizer = Normalizer::new(&aliases);
+
+    check_policy(content, licenses, &normalizer, diagnostics);
+    for scope in SCOPES {
+        if let Some(policy) = licenses.get(scope).and_then(Item::as_table_like) {
+            check_policy(content, policy, &normalizer, diagnostics);
+        }
+    }
+
+    if let Some(categories) = licenses.get("categories").and_then(Item::as_table_like) {
+        for (license, _) in categories.iter() {
+            let span = categories
+                .get_key_value(license)
+                .and_then(|(key, _)| key.span());
+            diagnostics.extend(check_license(content, &normalizer, license, span));
+        }
+    }
+}
+
+/// Checks the lists of licenses of a policy.
+fn check_policy(
+    content: &str,
+    policy: &dyn TableLike,
+    normalizer: &Normalizer,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if let (Some(_), Some((key, _))) = (policy.get("accepted"), policy.get_key_value("unaccepted"))
+    {
+        diagnostics.push(
+            Diagnostic::error(
+                "`accepted` and `unaccepted` can't be used at the same time, `unaccepted` would be ignored",
+            )
+            .at(content, key.span()),
+        );
+    }
+
+    for key in ["accepted", "unaccepted", "warned"] {
+        let Some(list) = policy.get(key).and_then(Item::as_array) else {
+            continue;
+        };
+        for value in list {
+            if let Some(license) = value.as_str() {
+                diagnostics.extend(check_license(content, normalizer, license, value.span()));
+            }
+        }
+    }
+}
+
+/// Warns about a license that is not a valid SPDX identifier.
+fn check_license(
+    content: &str,
+    normalizer: &Normalizer,
+    license: &str,
+    span: Option<Range<usize>>,
+) -> Option<Diagnostic> {
+    let license = license.strip_prefix('!').unwrap_or(license);
+    if Expression::parse(license).is_spdx() {
+        return None;
+    }
+    let normalized = normalizer.normalize(license);
+    let message = if Expression::parse(&normalized).is_spdx() {
+        format!("`{license}` is not a valid SPDX license identifier, it will be treated as `{normalized}`")
+    } else {
+        format!("`{license}` is not a valid SPDX license identifier, it will only match licenses declared exactly the same way")
+    };
+    Some(Diagnostic::warning(message).at(content, span))
+}
+
+/// Checks the values of the `[behavior]` section that can't be checked by their type.
+fn check_behavior(content: &str, behavior: &dyn TableLike, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(value) = behavior
+        .get("min_detection_confidence")
+        .and_then(Item::as_value)
+    else {
+        return;
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let confidence = value
+        .as_float()
+        .or_else(|| value.as_integer().map(|i| i as f64));
+    if confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+        diagnostics.push(
+            Diagnostic::error("`min_detection_confidence` must be a number between 0 and 1")
+                .at(content, value.span()),
+        );
+    }
+}
+
+/// Warns about the exceptions of the `ignored` list that have expired.
+fn check_exceptions(
+    content: &str,
+    document: &ImDocument<&str>,
+    licrc: &LicRc,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(ignored) = licrc.dependencies.ignored.as_ref() else {
+        return;
+    };
+    let today = Date::today();
+    let entries = document
+        .get("dependencies")
+        .and_then(|dependencies| dependencies.get("ignored"))
+        .and_then(Item::as_array);
+    for (index, exception) in ignored.iter().enumerate() {
+        if let (true, Some(expires)) = (exception.is_expired_at(today), exception.expires) {
+            let span = entries
+                .and_then(|entries| entries.get(index))
+                .and_then(toml_edit::Value::span);
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "the exception for `{}` expired on {expires} and it's no longer honoured",
+                    exception.name
+                ))
+                .at(content, span),
+            );
+        }
+    }
+}
+
+/// Suggests the most similar known key, if it's similar enough.
+fn suggest<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .map(|candidate| (distance(key, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        check(content).into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn known_keys_match_the_licrc_fields() {
+        let licrc = serde_json::to_value(LicRc::default()).unwrap();
+        let keys = |value: &serde_json::Value| {
+            let mut keys = value
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+        let sorted = |keys: &[&str]| {
+            let mut keys = keys.iter().map(ToString::to_string).collect::<Vec<_>>();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(&licrc["licenses"]), sorted(LICENSES_KEYS));
+        assert_eq!(keys(&licrc["dependencies"]), sorted(DEPENDENCIES_KEYS));
+        assert_eq!(keys(&licrc["behavior"]), sorted(BEHAVIOR_KEYS));
+        let scope = serde_json::to_value(super::super::LicRcScopeLicenses::default()).unwrap();
+        assert_eq!(keys(&scope), sorted(SCOPE_KEYS));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_suggestions() {
+        let content = "[licenses]\nacepted = [\"MIT\"]\n\n[dependecies]\nignored = []\n";
+        let diagnostics = check(content);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| d.message.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "unknown key `acepted` in `[licenses]`, did you mean `accepted`?",
+                "unknown key `dependecies` in the root of the file, did you mean `dependencies`?",
+            ]
+        );
+        let location = diagnostics[0].location.as_ref().unwrap();
+        assert_eq!((location.line, location.column, location.length), (2, 1, 7));
+        assert_eq!(diagnostics[1].location.as_ref().unwrap().line, 4);
+        assert!(diagnostics.iter().all(Diagnostic::is_error));
+    }
+
+    #[test]
+    fn accepted_and_unaccepted_are_incompatible() {
+        assert_eq!(
+            messages("[licenses]\naccepted = [\"MIT\"]\nunaccepted = [\"GPL-3.0-only\"]\n"),
+            vec!["`accepted` and `unaccepted` can't be used at the same time, `unaccepted` would be ignored"]
+        );
+    }
+
+    #[test]
+    fn scope_policies_are_checked() {
+        assert_eq!(
+            messages("[licenses.dev]\naccepted = [\"GPL-3.0-only\"]\naliases = {}\n"),
+            vec!["unknown key `aliases` in `[licenses.dev]`"]
+        );
+        assert_eq!(
+            check("[licenses.optional]\naccepted = [\"MIT\"]\nunaccepted = [\"Foo\"]\n").len(),
+            2
+        );
+    }
+
+    #[test]
+    fn detection_confidence_must_be_between_0_and_1() {
+        assert_eq!(
+            messages("[behavior]\nmin_detection_confidence = 80\n"),
+            vec!["`min_detection_confidence` must be a number between 0 and 1"]
+        );
+        assert!(check("[behavior]\nmin_detection_confidence = 0.9\n").is_empty());
+    }
+
+    #[test]
+    fn non_spdx_licenses_are_warnings() {
+        let diagnostics = check("[licenses]\naccepted = [\"MIT\", \"Apache 2.0\", \"Foo\"]\n");
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+        assert!(diagnostics[0].message.contains("treated as `Apache-2.0`"));
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().column, 20);
+    }
+
+    #[test]
+    fn invalid_values_and_syntax_errors_are_reported() {
+        let diagnostics = check("[behavior]\ndo_not_block_pr = \"yes\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().line, 2);
+        assert_eq!(check("[licenses\n").len(), 1);
+        assert!(
+            check("extends = \"base.licrc\"\n[behavior]\n\"!do_not_block_pr\" = true\n").is_empty()
+        );
+    }
+
+    #[test]
+    fn expired_exceptions_are_warnings() {
+        let diagnostics = check(
+            "[dependencies]\nignored = [\"foo\", { name = \"bar\", expires = 2020-01-31 }, { name = \"baz\", expires = 2999-01-31 }]\n",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert!(!diagnostics[0].is_error());
+        assert_eq!(
+            diagnostics[0].message,
+            "the exception for `bar` expired on 2020-01-31 and it's no longer honoured"
+        );
+        assert_eq!(diagnostics[0].location.as_ref().unwrap().column, 19);
+    }
+
+    #[test]
+    fn diagnostics_point_at_the_line() {
+        let diagnostic = check("[licenses]\nacepted = [\"MIT\"]\n").remove(0);
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: unknown key `acepted` in `[licenses]`, did you mean `accepted`?\n --> .licrc:2:1\n  |\n2 | acepted = [\"MIT\"]\n  | ^^^^^^^"
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use super::DependencyMatcher;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// An entry of the `ignored` list of the `.licrc` file.
+///
+/// It can be just a [`DependencyMatcher`] (`"openssl@<0.11"`) or a table with the justification of the exception:
+///
+/// ```toml
+/// ignored = [
+///   { name = "openssl@<0.11", reason = "Used only in tests", approved_by = "legal@acme.com", expires = 2027-01-31 },
+/// ]
+/// ```
+///
+/// Exceptions are honoured until the end of their expiry date.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "IgnoredRepr", into = "IgnoredRepr")]
+pub struct IgnoredDependency {
+    /// Dependencies covered by the exception.
+    pub name: DependencyMatcher,
+    /// Why the exception has been granted.
+    pub reason: Option<String>,
+    /// Who approved the exception.
+    pub approved_by: Option<String>,
+    /// Last day the exception is honoured.
+    pub expires: Option<Date>,
+}
+
+impl IgnoredDependency {
+    /// Checks if a dependency matches the name and the version requirement.
+    #[must_use]
+    pub fn matches(&self, name: &str, version: &str) -> bool {
+        self.name.matches(name, version)
+    }
+
+    /// Returns true if the exception is no longer honoured on the given date.
+    #[must_use]
+    pub fn is_expired_at(&self, today: Date) -> bool {
+        self.expires.is_some_and(|expires| expires < today)
+    }
+
+    /// Describes the exception (reason, approver and expiry date) to be used in the comments.
+    /// It returns `None` if the exception has no details.
+    #[must_use]
+    pub fn description(&self) -> Option<String> {
+        if self.reason.is_none() && self.approved_by.is_none() && self.expires.is_none() {
+            return None;
+        }
+        let mut description = self
+            .reason
+            .clone()
+            .unwrap_or_else(|| "No reason given".to_owned());
+        if let Some(approved_by) = &self.approved_by {
+            description.push_str(&format!(" (approved by {approved_by})"));
+        }
+        if let Some(expires) = &self.expires {
+            description.push_str(&format!(" - expires on {expires}"));
+        }
+        Some(description)
+    }
+}
+
+impl From<DependencyMatcher> for IgnoredDependency {
+    fn from(name: DependencyMatcher) -> Self {
+        Self {
+            name,
+            reason: None,
+            approved_by: None,
+            expires: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum IgnoredRepr {
+    Name(DependencyMatcher),
+    Exception(ExceptionRepr),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExceptionRepr {
+    name: DependencyMatcher,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    approved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<Date>,
+}
+
+impl From<IgnoredRepr> for IgnoredDependency {
+    fn from(repr: IgnoredRepr) -> Self {
+        match repr {
+            IgnoredRepr::Name(name) => name.into(),
+            IgnoredRepr::Exception(ExceptionRepr {
+                name,
+                reason,
+                approved_by,
+                expires,
+            }) => Self {
+                name,
+                reason,
+                approved_by,
+                expires,
+            },
+        }
+    }
+}
+
+impl From<IgnoredDependency> for IgnoredRepr {
+    fn from(ignored: IgnoredDependency) -> Self {
+        if ignored.reason.is_none() && ignored.approved_by.is_none() && ignored.expires.is_none() {
+            return Self::Name(ignored.name);
+        }
+        Self::Exception(ExceptionRepr {
+            name: ignored.name,
+            reason: ignored.reason,
+            approved_by: ignored.approved_by,
+            expires: ignored.expires,
+        })
+    }
+}
+
+/// A calendar date (`YYYY-MM-DD`).
+///
+/// It can be written as a TOML date (`2027-01-31`) or as a string (`"2027-01-31"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+/// Error produced when parsing a [`Date`].
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid date `{0}`, expected YYYY-MM-DD")]
+pub struct DateError(String);
+
+impl Date {
+    /// Creates a new [`Date`] if it's a valid one.
+    #[must_use]
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Returns the current date (UTC).
+    #[must_use]
+    pub fn today() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_days_since_epoch(i64::try_from(seconds / 86_400).unwrap_or(i64::MAX))
+    }
+
+    /// Converts the number of days since 1970-01-01 to a date.
+    /// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || DateError(s.to_owned());
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(error);
+        let (year, month, day) = (next()?, next()?, next()?);
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(error());
+        }
+        Self::new(
+            year.parse().map_err(|_| error())?,
+            month.parse().map_err(|_| error())?,
+            day.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DateVisitor;
+
+        impl<'de> de::Visitor<'de> for DateVisitor {
+            type Value = Date;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a date (YYYY-MM-DD)")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Date, E> {
+                value.parse().map_err(E::custom)
+            }
+
+            // TOML dates are exposed as a map with a single private key
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Date, A::Error> {
+                let Some((_, value)) = map.next_entry::<String, String>()? else {
+                    return Err(de::Error::custom("empty date"));
+                };
+                value.parse().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(DateVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn dates_are_parsed_and_validated() {
+        assert_eq!(date("2027-01-31").to_string(), "2027-01-31");
+        assert!(date("2027-01-31") < date("2027-02-01"));
+        assert!("2027-02-30".parse::<Date>().is_err());
+        assert!("2028-02-29".parse::<Date>().is_ok());
+        assert!("27-01-31".parse::<Date>().is_err());
+        assert!("2027-01".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn days_since_epoch_are_converted() {
+        assert_eq!(Date::from_days_since_epoch(0), date("1970-01-01"));
+        assert_eq!(Date::from_days_since_epoch(19_782), date("2024-02-29"));
+        assert!(Date::today() > date("2024-01-01"));
+    }
+
+    #[test]
+    fn exceptions_expire_after_their_date() {
+        let ignored: IgnoredDependency = serde_json::from_value(serde_json::json!({
+            "name": "openssl",
+            "reason": "Used only in tests",
+            "expires": "2027-01-31",
+        }))
+        .unwrap();
+        assert!(!ignored.is_expired_at(date("2027-01-31")));
+        assert!(ignored.is_expired_at(date("2027-02-01")));
+        assert_eq!(
+            ignored.description().as_deref(),
+            Some("Used only in tests - expires on 2027-01-31")
+        );
+        assert_eq!(IgnoredDependency::from(ignored.name).description(), None);
+    }
+
+    #[cfg(feature = "licrc-from-file")]
+    #[test]
+    fn exceptions_can_be_declared_in_toml() {
+        #[derive(Deserialize, Serialize)]
+        struct List {
+            ignored: Vec<IgnoredDependency>,
+        }
+        let list: List = toml::from_str(
+            r#"ignored = ["foo", { name = "bar@<2", reason = "Waiver", approved_by = "legal", expires = 2027-01-31 }]"#,
+        )
+        .unwrap();
+        assert_eq!(list.ignored[0].expires, None);
+        assert_eq!(list.ignored[1].expires, Some(date("2027-01-31")));
+        assert_eq!(list.ignored[1].approved_by.as_deref(), Some("legal"));
+        assert!(list.ignored[1].matches("bar", "1.0.0"));
+
+        let list: List = toml::from_str(&toml::to_string(&list).unwrap()).unwrap();
+        assert_eq!(list.ignored[1].expires, Some(date("2027-01-31")));
+    }
+}
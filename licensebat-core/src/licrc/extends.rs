@@ -0,0 +1,225 @@
+use super::{diagnostic, Diagnostic, Error};
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Key used to declare the base policies of a `.licrc` file.
+const EXTENDS: &str = "extends";
+
+/// Prefix used to remove inherited entries.
+const REMOVE_PREFIX: char = '!';
+
+/// Reads a `.licrc` file and resolves its `extends` key recursively.
+///
+/// Base policies are resolved relative to the file declaring them and merged in order,
+/// so every policy overrides the previous ones and the file itself overrides all of them.
+///
+/// Every file is checked and the problems found are added to the diagnostics.
+pub(super) fn resolve(path: &Path, diagnostics: &mut Vec<Diagnostic>) -> Result<Table, Error> {
+    resolve_inner(path, &mut vec![], diagnostics)
+}
+
+fn resolve_inner(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Table, Error> {
+    let path = path.canonicalize()?;
+    if stack.contains(&path) {
+        return Err(Error::ExtendsCycle(path));
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    diagnostics.extend(
+        diagnostic::check(&content)
+            .into_iter()
+            .map(|d| d.in_file(&path)),
+    );
+    let Ok(mut table) = toml::from_str::<Table>(&content) else {
+        // syntax errors have already been reported by the check
+        return Ok(Table::new());
+    };
+    let parents = match table.remove(EXTENDS) {
+        None => vec![],
+        Some(Value::String(parent)) => vec![parent],
+        Some(Value::Array(parents)) => parents
+            .into_iter()
+            .map(|parent| match parent {
+                Value::String(parent) => Ok(parent),
+                _ => Err(Error::InvalidExtends(path.clone())),
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(Error::InvalidExtends(path)),
+    };
+
+    let base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    stack.push(path);
+    let mut resolved = Table::new();
+    for parent in parents {
+        let parent = resolve_inner(&base_dir.join(parent), stack, diagnostics)?;
+        merge(&mut resolved, parent);
+    }
+    stack.pop();
+
+    merge(&mut resolved, table);
+    Ok(resolved)
+}
+
+/// Merges a child policy into a base one.
+///
+/// - Tables are merged recursively.
+/// - Lists are merged, keeping the base entries first and skipping duplicates.
+///   Entries prefixed with `!` remove the same entry (or the table with the same `name`) from the base list.
+/// - Any other value in the child overrides the one in the base.
+/// - Keys prefixed with `!` remove the same key from the base table.
+pub(super) fn merge(base: &mut Table, child: Table) {
+    for (key, value) in child {
+        if let Some(removed) = key.strip_prefix(REMOVE_PREFIX) {
+            base.remove(removed);
+            continue;
+        }
+        let merged = match (base.remove(&key), value) {
+            (Some(Value::Table(mut base)), Value::Table(child)) => {
+                merge(&mut base, child);
+                Value::Table(base)
+            }
+            (_, Value::Table(child)) => {
+                let mut table = Table::new();
+                merge(&mut table, child);
+                Value::Table(table)
+            }
+            (Some(Value::Array(mut base)), Value::Array(child)) => {
+                merge_lists(&mut base, child);
+                Value::Array(base)
+            }
+            (_, Value::Array(child)) => {
+                let mut list = vec![];
+                merge_lists(&mut list, child);
+                Value::Array(list)
+            }
+            (_, value) => value,
+        };
+        base.insert(key, merged);
+    }
+}
+
+fn merge_lists(base: &mut Vec<Value>, child: Vec<Value>) {
+    for value in child {
+        if let Some(removed) = value.as_str().and_then(|v| v.strip_prefix(REMOVE_PREFIX)) {
+            // exceptions of the `ignored` list are tables with a `name`
+            base.retain(|v| {
+                v.as_str().or_else(|| v.get("name").and_then(Value::as_str)) != Some(removed)
+            });
+        } else if !base.contains(&value) {
+            base.push(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(base: &str, child: &str) -> Table {
+        let mut base = toml::from_str(base).unwrap();
+        merge(&mut base, toml::from_str(child).unwrap());
+        base
+    }
+
+    #[test]
+    fn lists_are_merged_and_scalars_overridden() {
+        let result = merged(
+            r#"
+            [licenses]
+            accepted = ["MIT", "Apache-2.0"]
+            [behavior]
+            do_not_block_pr = false
+            retriever_buffer_size = 10
+            "#,
+            r#"
+            [licenses]
+            accepted = ["ISC", "MIT"]
+            [behavior]
+            do_not_block_pr = true
+            "#,
+        );
+        let expected: Table = toml::from_str(
+            r#"
+            [licenses]
+            accepted = ["MIT", "Apache-2.0", "ISC"]
+            [behavior]
+            do_not_block_pr = true
+            retriever_buffer_size = 10
+            "#,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn inherited_entries_can_be_removed() {
+        let result = merged(
+            r#"
+            [licenses]
+            accepted = ["MIT", "GPL-3.0-only"]
+            [dependencies.overrides]
+            foo = "MIT"
+            bar = "ISC"
+            [behavior]
+            retriever_buffer_size = 10
+            "#,
+            r#"
+            [licenses]
+            accepted = ["!GPL-3.0-only", "!Zlib"]
+            [dependencies.overrides]
+            "!foo" = ""
+            [behavior]
+            "!retriever_buffer_size" = 0
+            "#,
+        );
+        let expected: Table = toml::from_str(
+            r#"
+            [licenses]
+            accepted = ["MIT"]
+            [dependencies.overrides]
+            bar = "ISC"
+            [behavior]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn extends_are_resolved_relative_to_the_declaring_file() {
+        let dir = std::env::temp_dir().join(format!("licensebat-extends-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("policies")).unwrap();
+        std::fs::create_dir_all(dir.join("repo")).unwrap();
+        std::fs::write(
+            dir.join("policies/base.licrc"),
+            "extends = \"common.licrc\"\n[licenses]\naccepted = [\"MIT\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("policies/common.licrc"),
+            "[licenses]\naccepted = [\"Zlib\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("repo/.licrc"),
+            "extends = [\"../policies/base.licrc\"]\n[licenses]\naccepted = [\"ISC\", \"!Zlib\"]\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("repo/cycle.licrc"), "extends = \"cycle.licrc\"\n").unwrap();
+
+        let mut diagnostics = vec![];
+        let resolved = resolve(&dir.join("repo/.licrc"), &mut diagnostics);
+        let cycle = resolve(&dir.join("repo/cycle.licrc"), &mut diagnostics);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let expected: Table =
+            toml::from_str("[licenses]\naccepted = [\"MIT\", \"ISC\"]\n").unwrap();
+        assert_eq!(resolved.unwrap(), expected);
+        assert!(matches!(cycle, Err(Error::ExtendsCycle(_))));
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Matches dependencies by name and, optionally, by version.
+///
+/// It's used for the entries of the `ignored` list in the `.licrc` file, which can be:
+///
+/// - A name: `openssl`.
+/// - A name with `*` wildcards: `@acme/*` or `acme-*`.
+/// - Any of the above followed by `@` and a version requirement: `openssl@<0.11`, `name@=1.2.3` or `@acme/*@^2`.
+///
+/// Version requirements use the semver syntax shared by npm, cargo and pub (`^1.2`, `~1.2.3`, `>=1.0.0 <2.0.0`, `1.x`...).
+/// A bare version is an exact requirement (`1.2.3` is the same as `=1.2.3`), like in npm and pub but unlike in `Cargo.toml`,
+/// and npm hyphen ranges (`1.0.0 - 2.0.0`) are inclusive on both ends.
+/// Alternatives can be expressed with `||`. Versions that are not valid semver will only match exact (`=`) requirements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct DependencyMatcher {
+    original: String,
+    name: String,
+    version: Option<Vec<semver::VersionReq>>,
+}
+
+/// Error produced when parsing a [`DependencyMatcher`].
+#[derive(Debug, thiserror::Error)]
+pub enum MatcherError {
+    /// The matcher has no name.
+    #[error("Empty dependency name in `{0}`")]
+    EmptyName(String),
+    /// The version requirement is not valid.
+    #[error("Invalid version requirement in `{0}`: {1}")]
+    Version(String, semver::Error),
+}
+
+impl DependencyMatcher {
+    /// Checks if a dependency matches the name and the version requirement.
+    #[must_use]
+    pub fn matches(&self, name: &str, version: &str) -> bool {
+        wildcard_match(&self.name, name) && self.matches_version(version)
+    }
+
+    fn matches_version(&self, version: &str) -> bool {
+        let Some(requirements) = self.version.as_ref() else {
+            return true;
+        };
+        semver::Version::parse(version.trim_start_matches('v')).map_or_else(
+            |_| {
+                // not semver, let's compare it literally
+                self.original
+                    .rsplit_once('@')
+                    .is_some_and(|(_, req)| req.trim().trim_start_matches('=') == version)
+            },
+            |version| requirements.iter().any(|req| req.matches(&version)),
+        )
+    }
+}
+
+impl FromStr for DependencyMatcher {
+    type Err = MatcherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let original = s.trim().to_owned();
+        // the first char is skipped so scoped npm packages (@scope/name) work
+        let separator = original
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '@')
+            .map(|(index, _)| index);
+
+        let (name, version) = separator.map_or((original.as_str(), None), |index| {
+            (&original[..index], Some(&original[index + 1..]))
+        });
+
+        if name.is_empty() {
+            return Err(MatcherError::EmptyName(original));
+        }
+
+        let version = version
+            .map(|version| {
+                version
+                    .split("||")
+                    .map(|req| semver::VersionReq::parse(&to_comma_separated(req)))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()
+            .map_err(|e| MatcherError::Version(original.clone(), e))?;
+
+        Ok(Self {
+            name: name.to_owned(),
+            version,
+            original,
+        })
+    }
+}
+
+impl TryFrom<String> for DependencyMatcher {
+    type Error = MatcherError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<DependencyMatcher> for String {
+    fn from(matcher: DependencyMatcher) -> Self {
+        matcher.original
+    }
+}
+
+impl fmt::Display for DependencyMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+
+/// npm and pub separate comparators with whitespace (`>=1.0.0 <2.0.0`) while cargo uses commas.
+///
+/// Bare versions become exact requirements and hyphen ranges become a pair of inclusive comparators.
+fn to_comma_separated(req: &str) -> String {
+    let tokens = req
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>();
+    if let [from, "-", to] = tokens.as_slice() {
+        return format!(">={from}, <={to}");
+    }
+
+    let mut comparators: Vec<String> = vec![];
+    let mut pending_operator = String::new();
+    for token in tokens {
+        if token
+            .chars()
+            .all(|c| matches!(c, '<' | '>' | '=' | '~' | '^'))
+        {
+            pending_operator.push_str(token);
+        } else {
+            let is_bare =
+                pending_operator.is_empty() && token.starts_with(|c: char| c.is_ascii_digit());
+            // prerelease and build metadata (i.e. `1.0.0-next.1`) can't have wildcards
+            let core = token.split(['-', '+']).next().unwrap_or_default();
+            if is_bare && !core.contains(['*', 'x', 'X']) {
+                pending_operator.push('=');
+            }
+            comparators.push(format!("{pending_operator}{token}"));
+            pending_operator.clear();
+        }
+    }
+    comparators.join(", ")
+}
+
+/// Matches a text against a pattern where `*` matches any sequence of characters.
+pub(crate) fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // there's always at least one part
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        // no wildcards
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(s: &str) -> DependencyMatcher {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn plain_names_match_every_version() {
+        let m = matcher("openssl");
+        assert!(m.matches("openssl", "0.10.0"));
+        assert!(!m.matches("openssl-sys", "0.10.0"));
+    }
+
+    #[test]
+    fn wildcards_match_names() {
+        assert!(matcher("@acme/*").matches("@acme/ui", "1.0.0"));
+        assert!(!matcher("@acme/*").matches("@other/ui", "1.0.0"));
+        assert!(matcher("acme-*").matches("acme-core", "1.0.0"));
+        assert!(!matcher("acme-*").matches("acme", "1.0.0"));
+        assert!(matcher("*-sys").matches("openssl-sys", "1.0.0"));
+        assert!(matcher("a*b*c").matches("a-b-c", "1.0.0"));
+        assert!(!matcher("a*b*c").matches("a-c-b", "1.0.0"));
+    }
+
+    #[test]
+    fn version_requirements_are_honored() {
+        let m = matcher("openssl@<0.11");
+        assert!(m.matches("openssl", "0.10.55"));
+        assert!(!m.matches("openssl", "0.11.0"));
+
+        let m = matcher("name@=1.2.3");
+        assert!(m.matches("name", "1.2.3"));
+        assert!(!m.matches("name", "1.2.4"));
+
+        let m = matcher("@acme/ui@^2 || ^4");
+        assert!(m.matches("@acme/ui", "2.3.0"));
+        assert!(m.matches("@acme/ui", "4.0.0"));
+        assert!(!m.matches("@acme/ui", "3.0.0"));
+    }
+
+    #[test]
+    fn whitespace_separated_comparators_are_supported() {
+        let m = matcher("foo@>=1.0.0 <2.0.0");
+        assert!(m.matches("foo", "1.5.0"));
+        assert!(!m.matches("foo", "2.0.0"));
+        let m = matcher("foo@>= 1.0.0, < 2.0.0");
+        assert!(m.matches("foo", "1.5.0"));
+    }
+
+    #[test]
+    fn bare_versions_are_exact_requirements() {
+        let m = matcher("openssl@0.10.55");
+        assert!(m.matches("openssl", "0.10.55"));
+        assert!(!m.matches("openssl", "0.10.56"));
+        assert!(!m.matches("openssl", "0.11.0"));
+
+        let m = matcher("foo@1.0.0-next.1");
+        assert!(m.matches("foo", "1.0.0-next.1"));
+        assert!(!m.matches("foo", "1.5.0"));
+
+        let m = matcher("foo@1.x");
+        assert!(m.matches("foo", "1.5.0"));
+        assert!(!m.matches("foo", "2.0.0"));
+    }
+
+    #[test]
+    fn hyphen_ranges_are_inclusive() {
+        let m = matcher("foo@1.0.0 - 2.0.0");
+        assert!(!m.matches("foo", "0.9.0"));
+        assert!(m.matches("foo", "1.0.0"));
+        assert!(m.matches("foo", "2.0.0"));
+        assert!(!m.matches("foo", "2.0.1"));
+
+        let m = matcher("foo@1.0.0 - 1.2.0 || 3.0.0");
+        assert!(m.matches("foo", "1.1.0"));
+        assert!(m.matches("foo", "3.0.0"));
+        assert!(!m.matches("foo", "3.0.1"));
+
+        assert!("foo@1.0.0 - 2.0.0 - 3.0.0"
+            .parse::<DependencyMatcher>()
+            .is_err());
+        assert!("foo@- 2.0.0".parse::<DependencyMatcher>().is_err());
+    }
+
+    #[test]
+    fn non_semver_versions_only_match_exact_requirements() {
+        assert!(matcher("foo@=2021.1").matches("foo", "2021.1"));
+        assert!(!matcher("foo@<3000").matches("foo", "2021.1"));
+    }
+
+    #[test]
+    fn invalid_requirements_are_errors() {
+        assert!("foo@<<1".parse::<DependencyMatcher>().is_err());
+        assert!("foo@".parse::<DependencyMatcher>().is_err());
+        assert!("".parse::<DependencyMatcher>().is_err());
+    }
+}
@@ -0,0 +1,977 @@
+//! Exposes a struct to manage the `.licrc` file information and validate the dependencies accordingly.
+//!
+//! When using the `licrc-from-file` feature, a [`LicRc::from_relative_path`] associated function will be available for you to load the information from a file.
+//!
+//! A `.licrc` file can extend other policies with the `extends` key (i.e. `extends = ["../policies/base.licrc"]`).
+//! Paths are relative to the file declaring them and the policies are merged in order, the file itself being the last one:
+//!
+//! - Lists are merged, skipping duplicates. Entries prefixed with `!` remove the inherited entry (i.e. `accepted = ["!GPL-3.0-only"]`).
+//! - Tables are merged recursively. Keys prefixed with `!` remove the inherited key (i.e. `"!my_crate" = ""` in `[dependencies.overrides]`).
+//! - Any other value overrides the inherited one.
+use crate::{
+    license::{
+        Category, Classifier, Expression, LicenseExpression, LicenseMatcher, Normalizer, Term,
+    },
+    Comment, Dependency, FailureReason, PolicyScope, RetrievedDependency, Verdict,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::instrument;
+
+#[cfg(feature = "licrc-from-file")]
+mod diagnostic;
+mod exception;
+#[cfg(feature = "licrc-from-file")]
+mod extends;
+mod matcher;
+
+#[cfg(feature = "licrc-from-file")]
+pub use diagnostic::{Diagnostic, Location, Severity};
+pub use exception::{Date, DateError, IgnoredDependency};
+pub(crate) use matcher::wildcard_match;
+pub use matcher::{DependencyMatcher, MatcherError};
+
+/// Default value of the `min_detection_confidence` option of the `[behavior]` section.
+pub const DEFAULT_MIN_DETECTION_CONFIDENCE: f32 = 0.8;
+
+/// Represents the `.licrc` configuration file.
+/// This file is the one used in your project to define which licenses are accepted/unaccepted
+/// and which dependencies should be ignored.
+///
+/// Unknown keys are rejected, so typos don't go unnoticed.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LicRc {
+    /// List of accepted and unaccepted licenses.
+    #[serde(default)]
+    pub licenses: LicRcLicenses,
+    /// List of ignored dependencies and dependency settings.
+    #[serde(default)]
+    pub dependencies: LicRcDependencies,
+    /// Properties that affect the behavior of the validation.
+    #[serde(default)]
+    pub behavior: LicRcBehavior,
+}
+
+/// Error raised by a collector while parsing/getting the dependencies.
+#[cfg(feature = "licrc-from-file")]
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Error trying to open or read the .licrc file
+    #[error("Error trying to open and read the .licrc file: {0}")]
+    Io(#[from] std::io::Error),
+    /// Error parsing the .licrc file
+    #[error("Error trying to parse the .licrc file: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// The `extends` key is not a path or a list of paths
+    #[error("The `extends` key must be a path or a list of paths in {0}")]
+    InvalidExtends(std::path::PathBuf),
+    /// A .licrc file extends itself, directly or through other files
+    #[error("Circular `extends` found in {0}")]
+    ExtendsCycle(std::path::PathBuf),
+    /// The .licrc file has some errors
+    #[error("Invalid .licrc file:\n{}", display_diagnostics(.0))]
+    Invalid(Vec<Diagnostic>),
+}
+
+#[cfg(feature = "licrc-from-file")]
+fn display_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(feature = "licrc-from-file")]
+impl LicRc {
+    /// Loads a .licrc from a relative path, resolving all the policies it extends.
+    /// You must compile this crate with the `licrc-from-file` feature for this to be available.
+    ///
+    /// The file is strictly validated: unknown keys, invalid values or incompatible options will make it fail with an [`Error::Invalid`].
+    /// Warnings, like licenses that are not valid SPDX identifiers, are only logged.
+    #[instrument(skip(relative_path))]
+    pub fn from_relative_path(relative_path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let (licrc, diagnostics) = Self::load(relative_path)?;
+        for warning in diagnostics.iter().filter(|d| !d.is_error()) {
+            tracing::warn!("{warning}");
+        }
+        licrc.ok_or(Error::Invalid(diagnostics))
+    }
+
+    /// Checks a .licrc file and all the policies it extends, returning all the problems found.
+    /// You must compile this crate with the `licrc-from-file` feature for this to be available.
+    ///
+    /// # Errors
+    ///
+    /// It only fails if the files can't be read or there's an issue with the `extends` key.
+    /// Any other problem is returned as a [`Diagnostic`].
+    pub fn check_relative_path(
+        relative_path: impl AsRef<std::path::Path>,
+    ) -> Result<Vec<Diagnostic>, Error> {
+        Self::load(relative_path).map(|(_, diagnostics)| diagnostics)
+    }
+
+    /// Loads a .licrc file. The [`LicRc`] will be `None` if any error has been found.
+    fn load(
+        relative_path: impl AsRef<std::path::Path>,
+    ) -> Result<(Option<Self>, Vec<Diagnostic>), Error> {
+        let licrc_path = std::env::current_dir()?.join(relative_path);
+        let mut diagnostics = vec![];
+        let resolved = extends::resolve(&licrc_path, &mut diagnostics)?;
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            return Ok((None, diagnostics));
+        }
+
+        let error = match resolved.try_into::<Self>() {
+            Ok(licrc) if licrc.licenses.accepted.is_some() && licrc.licenses.unaccepted.is_some() => {
+                Diagnostic::error("`accepted` and `unaccepted` can't be used at the same time once the extended policies are merged, `unaccepted` would be ignored")
+            }
+            Ok(licrc) => return Ok((Some(licrc), diagnostics)),
+            Err(e) => Diagnostic::error(e.message().trim()),
+        };
+        diagnostics.push(error.in_file(licrc_path));
+        Ok((None, diagnostics))
+    }
+}
+
+impl LicRc {
+    /// Checks if a dependency should be ignored or not.
+    /// Note that this function will set the dependency's `is_ignored` property to `true` if it's ignored.
+    ///
+    /// Exceptions of the `ignored` list are not honoured after their expiry date.
+    /// In that case, the dependency's `is_exception_expired` property will be set to `true`.
+    pub fn is_ignored(&self, dependency: &mut RetrievedDependency) -> bool {
+        self.is_ignored_at(dependency, Date::today())
+    }
+
+    /// Same as [`LicRc::is_ignored`] but using the given date to check the expiry of the exceptions.
+    pub fn is_ignored_at(&self, dependency: &mut RetrievedDependency, today: Date) -> bool {
+        // is it explicitly ignored?
+        if let Some(exception) =
+            self.dependencies
+                .get_exception(&dependency.name, &dependency.version, today)
+        {
+            let description = exception.description();
+            if exception.is_expired_at(today) {
+                dependency.is_exception_expired = true;
+                dependency.add_comment(format!(
+                    "The exception for this dependency in the .licrc file has expired: {}.",
+                    description.unwrap_or_default()
+                ));
+            } else {
+                dependency.is_ignored = true;
+                if let Some(description) = description {
+                    dependency.add_comment(format!("Ignored by the .licrc file: {description}."));
+                }
+                return true;
+            }
+        }
+
+        // are dev dependencies ignored?
+        if self.dependencies.ignore_dev_dependencies && dependency.is_dev.unwrap_or(false) {
+            dependency.is_ignored = true;
+            return true;
+        }
+
+        // are optional dependencies ignored?
+        if self.dependencies.ignore_optional_dependencies && dependency.is_optional.unwrap_or(false)
+        {
+            dependency.is_ignored = true;
+            return true;
+        }
+
+        // are transitive dependencies ignored?
+        if self.dependencies.ignore_transitive_dependencies && dependency.is_direct == Some(false) {
+            dependency.is_ignored = true;
+            return true;
+        }
+        false
+    }
+
+    /// Returns the license declared for a dependency in the overrides of the `.licrc` file, if any.
+    /// Overrides declared for a specific version (`name@version`) take precedence over the ones declared only by name.
+    #[must_use]
+    pub fn get_override(&self, dependency: &Dependency) -> Option<&str> {
+        let overrides = self.dependencies.overrides.as_ref()?;
+        overrides
+            .get(&format!("{}@{}", dependency.name, dependency.version))
+            .or_else(|| overrides.get(&dependency.name))
+            .map(String::as_str)
+    }
+
+    /// Checks if a dependency should be retrieved or not.
+    pub fn filter_dependencies_before_retrieval(&self, dependency: &Dependency) -> bool {
+        let is_dev = dependency.is_dev.unwrap_or_default();
+        let is_optional = dependency.is_optional.unwrap_or_default();
+        let is_transitive = dependency.is_direct == Some(false);
+
+        if self.behavior.do_not_show_dev_dependencies && is_dev {
+            return false;
+        }
+        if self.behavior.do_not_show_optional_dependencies && is_optional {
+            return false;
+        }
+
+        let is_ignored = self
+            .dependencies
+            .is_explicitly_ignored(&dependency.name, &dependency.version);
+
+        if self.behavior.do_not_show_ignored_dependencies {
+            if is_ignored {
+                return false;
+            }
+            if self.dependencies.ignore_dev_dependencies && is_dev {
+                return false;
+            }
+            if self.dependencies.ignore_optional_dependencies && is_optional {
+                return false;
+            }
+            if self.dependencies.ignore_transitive_dependencies && is_transitive {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Validates a specific [`RetrievedDependency`].
+    /// Note that it will set the dependency's `validated` property to `true`.
+    /// While checking it's validaty against what's been declared in the `.licrc` file it can also modify `is_ignored` and `is_valid` properties.
+    #[instrument(skip(self))]
+    pub fn validate(&self, dependency: &mut RetrievedDependency) {
+        dependency.validated = true;
+        // is it ignored?
+        if self.is_ignored(dependency) {
+            tracing::debug!(dependency = ?dependency, "Dependency has been ignored");
+            return;
+        }
+        // is it compliant with the policy?
+        if !dependency.is_valid {
+            tracing::debug!(dependency = ?dependency, "Dependency is invalid");
+            return;
+        }
+
+        dependency.licenses.clone().map_or_else(
+            || {
+                tracing::error!("Licenses are None!! At this point, this shouldn't happen. Check out the dependency validation logic");
+            },
+            |licenses| {
+                let scope = self.licenses.scope_of(dependency);
+                let policy = self.licenses.for_scope(scope);
+                dependency.policy = Some(scope);
+                let (verdict, category) = policy.evaluate_licenses(&licenses);
+                // point at the declarations responsible for the verdict, if any
+                let culprit = || {
+                    licenses
+                        .licenses()
+                        .into_iter()
+                        .find(|lic| policy.evaluate(&Expression::parse(lic)).0 == verdict)
+                        .map_or_else(|| licenses.to_string(), ToOwned::to_owned)
+                };
+                match verdict {
+                    Verdict::Deny => make_invalid(dependency, &culprit()),
+                    Verdict::Warn => make_warned(dependency, &culprit()),
+                    Verdict::Allow => (),
+                }
+                dependency.category = category;
+            },
+        );
+    }
+}
+
+/// Marks a dependency as invalid and if it doesnt' have any error it adds one saying `Not compliant`.
+#[instrument]
+fn make_invalid(dependency: &mut RetrievedDependency, license: &str) {
+    tracing::debug!(
+        ?dependency,
+        license,
+        "No compliant dependency marked as invalid"
+    );
+    dependency.is_valid = false;
+    dependency.verdict = Verdict::Deny;
+    // preserve pre-existing error
+    if dependency.error.is_none() {
+        dependency.error = Some("Not compliant".to_string());
+        dependency.failure_reason = Some(FailureReason::NotCompliant);
+    }
+}
+
+/// Flags a dependency with a warning unless it has already been denied.
+#[instrument]
+fn make_warned(dependency: &mut RetrievedDependency, license: &str) {
+    tracing::debug!(?dependency, license, "Dependency flagged with a warning");
+    if dependency.verdict == Verdict::Deny {
+        return;
+    }
+    dependency.verdict = Verdict::Warn;
+    if dependency.comment.is_none() {
+        dependency.comment = Some(Comment::non_removable(format!(
+            "The license `{license}` is flagged as a warning in the .licrc file."
+        )));
+    }
+}
+
+/// Holds information about the accepted or unaccepted licenses.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LicRcLicenses {
+    /// List of accepted licenses (see <https://spdx.org/licenses/>)
+    pub accepted: Option<Vec<String>>,
+    /// List of unaccepted licenses (see <https://spdx.org/licenses/>)
+    pub unaccepted: Option<Vec<String>>,
+    /// List of licenses that should be reported as a warning without blocking the check (see <https://spdx.org/licenses/>)
+    pub warned: Option<Vec<String>>,
+    /// User defined aliases for license identifiers (i.e. `"Acme License" = "LicenseRef-Acme"`).
+    /// They take precedence over the built-in aliases of the [`Normalizer`].
+    pub aliases: Option<BTreeMap<String, String>>,
+    /// List of accepted license categories (i.e. `permissive` or `public-domain`).
+    pub allowed_categories: Option<Vec<Category>>,
+    /// List of unaccepted license categories (i.e. `strong-copyleft` or `network-copyleft`).
+    pub denied_categories: Option<Vec<Category>>,
+    /// List of license categories that should be reported as a warning without blocking the check (i.e. `weak-copyleft`).
+    pub warned_categories: Option<Vec<Category>>,
+    /// User defined categories for license identifiers (i.e. `"LicenseRef-Acme" = "proprietary"`).
+    /// They take precedence over the built-in classification of the [`Classifier`].
+    pub categories: Option<BTreeMap<String, Category>>,
+    /// Policy for dev dependencies. Its lists replace the ones of the main policy.
+    pub dev: Option<LicRcScopeLicenses>,
+    /// Policy for optional dependencies. Its lists replace the ones of the main policy.
+    pub optional: Option<LicRcScopeLicenses>,
+    /// Policy for transitive dependencies. Its lists replace the ones of the main policy.
+    pub transitive: Option<LicRcScopeLicenses>,
+}
+
+/// Holds the licenses accepted or unaccepted for some kind of dependencies (i.e. `[licenses.dev]`).
+///
+/// Any list that is not informed is inherited from the main policy.
+/// Note that `accepted` and `unaccepted` are inherited together, so declaring one of them discards both lists of the main policy.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LicRcScopeLicenses {
+    /// List of accepted licenses (see <https://spdx.org/licenses/>)
+    pub accepted: Option<Vec<String>>,
+    /// List of unaccepted licenses (see <https://spdx.org/licenses/>)
+    pub unaccepted: Option<Vec<String>>,
+    /// List of licenses that should be reported as a warning without blocking the check (see <https://spdx.org/licenses/>)
+    pub warned: Option<Vec<String>>,
+    /// List of accepted license categories.
+    pub allowed_categories: Option<Vec<Category>>,
+    /// List of unaccepted license categories.
+    pub denied_categories: Option<Vec<Category>>,
+    /// List of license categories that should be reported as a warning without blocking the check.
+    pub warned_categories: Option<Vec<Category>>,
+}
+
+impl LicRcLicenses {
+    /// Evaluates a license [`Expression`] against the accepted or unaccepted licenses.
+    /// Warned licenses are considered accepted.
+    ///
+    /// See [`LicRcLicenses::evaluate`] for the details.
+    #[must_use]
+    pub fn is_accepted(&self, expression: &Expression) -> bool {
+        self.evaluate(expression).0 != Verdict::Deny
+    }
+
+    /// Evaluates a license [`Expression`] against the policy and returns its [`Verdict`]
+    /// along with the [`Category`] of the licenses that have been chosen to satisfy it.
+    ///
+    /// Every term of the expression is checked in this order:
+    ///
+    /// 1. If it's in the `warned` list, it's warned.
+    /// 2. If it's in the `accepted` list, it's allowed.
+    /// 3. If there's no `accepted` list and it's in the `unaccepted` one, it's denied.
+    /// 4. If its category is in `denied_categories`, it's denied.
+    /// 5. If its category is in `warned_categories`, it's warned.
+    /// 6. If its category is in `allowed_categories`, it's allowed.
+    /// 7. Otherwise, it's denied if `accepted` or `allowed_categories` are informed and allowed if not.
+    ///
+    /// Then the terms are combined according to the operators of the expression, so
+    /// `OR` picks the best choice (best verdict and most permissive category) and `AND` picks the worst term.
+    ///
+    /// Note that the licenses declared in the `.licrc` file are normalized too.
+    #[must_use]
+    pub fn evaluate(&self, expression: &Expression) -> (Verdict, Option<Category>) {
+        expression.resolve(self.term_evaluator())
+    }
+
+    /// Evaluates the [`LicenseExpression`] of a dependency against the policy, like [`LicRcLicenses::evaluate`] does.
+    ///
+    /// `AnyOf` picks the best choice among its declarations and `AllOf` the worst one.
+    #[must_use]
+    pub fn evaluate_licenses(&self, licenses: &LicenseExpression) -> (Verdict, Option<Category>) {
+        licenses.resolve(self.term_evaluator())
+    }
+
+    /// Builds the function evaluating a single term of an expression against the policy.
+    fn term_evaluator(&self) -> impl Fn(&Term) -> (Verdict, Option<Category>) + '_ {
+        let normalizer = self.normalizer();
+        let classifier = self.classifier();
+        let parse = |licenses: &Option<Vec<String>>| {
+            licenses
+                .as_ref()
+                .map(|licenses| to_matchers(licenses, &normalizer))
+        };
+        let accepted = parse(&self.accepted);
+        let unaccepted = parse(&self.unaccepted);
+        let warned = parse(&self.warned);
+
+        move |term| {
+            let category = classifier.classify(term);
+            // accepted licenses cover any version of an or-later term they allow,
+            // while unaccepted and warned ones need to cover all of them
+            let matches = |matchers: &Option<Vec<LicenseMatcher>>| {
+                matchers
+                    .as_ref()
+                    .is_some_and(|matchers| matchers.iter().any(|m| m.matches(term)))
+            };
+            let includes = |matchers: &Option<Vec<LicenseMatcher>>| {
+                matchers
+                    .as_ref()
+                    .is_some_and(|matchers| matchers.iter().any(|m| m.includes(term)))
+            };
+            let is_in = |categories: &Option<Vec<Category>>| {
+                category.is_some_and(|c| categories.as_ref().is_some_and(|cs| cs.contains(&c)))
+            };
+
+            let verdict = if includes(&warned) {
+                Verdict::Warn
+            } else if matches(&accepted) {
+                Verdict::Allow
+            } else if (accepted.is_none() && includes(&unaccepted))
+                || is_in(&self.denied_categories)
+            {
+                Verdict::Deny
+            } else if is_in(&self.warned_categories) {
+                Verdict::Warn
+            } else if is_in(&self.allowed_categories)
+                || (accepted.is_none() && self.allowed_categories.is_none())
+            {
+                Verdict::Allow
+            } else {
+                Verdict::Deny
+            };
+            (verdict, category)
+        }
+    }
+
+    /// Returns the [`PolicyScope`] that applies to a dependency.
+    ///
+    /// Dev dependencies use the `[licenses.dev]` section, optional dependencies the `[licenses.optional]` one
+    /// and transitive dependencies the `[licenses.transitive]` one, if they exist.
+    /// When several scopes apply, dev takes precedence over optional and optional over transitive.
+    #[must_use]
+    pub fn scope_of(&self, dependency: &RetrievedDependency) -> PolicyScope {
+        if self.dev.is_some() && dependency.is_dev.unwrap_or_default() {
+            PolicyScope::Dev
+        } else if self.optional.is_some() && dependency.is_optional.unwrap_or_default() {
+            PolicyScope::Optional
+        } else if self.transitive.is_some() && dependency.is_direct == Some(false) {
+            PolicyScope::Transitive
+        } else {
+            PolicyScope::Default
+        }
+    }
+
+    /// Returns the effective policy for a [`PolicyScope`],
+    /// that is, the main policy with the lists declared in the section of the scope replacing the inherited ones.
+    #[must_use]
+    pub fn for_scope(&self, scope: PolicyScope) -> Self {
+        let scoped = match scope {
+            PolicyScope::Default => None,
+            PolicyScope::Dev => self.dev.as_ref(),
+            PolicyScope::Optional => self.optional.as_ref(),
+            PolicyScope::Transitive => self.transitive.as_ref(),
+        };
+        let Some(scoped) = scoped else {
+            return self.clone();
+        };
+        let (accepted, unaccepted) = if scoped.accepted.is_some() || scoped.unaccepted.is_some() {
+            (scoped.accepted.clone(), scoped.unaccepted.clone())
+        } else {
+            (self.accepted.clone(), self.unaccepted.clone())
+        };
+        Self {
+            accepted,
+            unaccepted,
+            warned: scoped.warned.clone().or_else(|| self.warned.clone()),
+            aliases: self.aliases.clone(),
+            allowed_categories: scoped
+                .allowed_categories
+                .clone()
+                .or_else(|| self.allowed_categories.clone()),
+            denied_categories: scoped
+                .denied_categories
+                .clone()
+                .or_else(|| self.denied_categories.clone()),
+            warned_categories: scoped
+                .warned_categories
+                .clone()
+                .or_else(|| self.warned_categories.clone()),
+            categories: self.categories.clone(),
+            dev: None,
+            optional: None,
+            transitive: None,
+        }
+    }
+
+    /// Returns a [`Normalizer`] using the aliases declared in the `.licrc` file.
+    #[must_use]
+    pub fn normalizer(&self) -> Normalizer {
+        self.aliases
+            .as_ref()
+            .map_or_else(Normalizer::default, Normalizer::new)
+    }
+
+    /// Returns a [`Classifier`] using the categories declared in the `.licrc` file.
+    #[must_use]
+    pub fn classifier(&self) -> Classifier {
+        self.categories
+            .as_ref()
+            .map_or_else(Classifier::default, Classifier::new)
+    }
+}
+
+fn to_matchers(licenses: &[String], normalizer: &Normalizer) -> Vec<LicenseMatcher> {
+    licenses
+        .iter()
+        .map(|l| LicenseMatcher::parse(&normalizer.normalize(l)))
+        .collect()
+}
+
+/// Holds information about dependency specifics.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LicRcDependencies {
+    /// List of ignored dependencies.
+    /// These dependencies won't be validated.
+    /// Entries can use `*` wildcards in the name and a version requirement (see [`DependencyMatcher`])
+    /// and they can be justified with a reason, an approver and an expiry date (see [`IgnoredDependency`]).
+    pub ignored: Option<Vec<IgnoredDependency>>,
+    /// Licenses declared manually for some dependencies.
+    /// Keys can be the name of the dependency or `name@version` and values are the license (or SPDX expression) to use.
+    /// These dependencies won't be retrieved from their sources, but they will be validated as any other dependency.
+    pub overrides: Option<BTreeMap<String, String>>,
+    /// If set to true, dev dependencies will be ignored.
+    #[serde(default)]
+    pub ignore_dev_dependencies: bool,
+    /// If set to true, optional dependencies will be ignored.
+    #[serde(default)]
+    pub ignore_optional_dependencies: bool,
+    /// If set to true, transitive dependencies will be ignored, so the policy is only enforced on direct dependencies.
+    /// Dependencies whose collector can't determine if they're direct are never ignored by this option.
+    #[serde(default)]
+    pub ignore_transitive_dependencies: bool,
+}
+
+impl LicRcDependencies {
+    /// Checks if a dependency matches any of the entries of the `ignored` list that haven't expired.
+    #[must_use]
+    pub fn is_explicitly_ignored(&self, name: &str, version: &str) -> bool {
+        let today = Date::today();
+        self.get_exception(name, version, today)
+            .is_some_and(|exception| !exception.is_expired_at(today))
+    }
+
+    /// Returns the entry of the `ignored` list matching a dependency, if any.
+    /// Entries that haven't expired on the given date take precedence.
+    #[must_use]
+    pub fn get_exception(
+        &self,
+        name: &str,
+        version: &str,
+        today: Date,
+    ) -> Option<&IgnoredDependency> {
+        self.ignored
+            .as_ref()?
+            .iter()
+            .filter(|exception| exception.matches(name, version))
+            .min_by_key(|exception| exception.is_expired_at(today))
+    }
+}
+
+/// Holds information about the behavior of the validation process.
+/// **This only applies for the [GITHUB API integrated project](https://github.com/marketplace/licensebat)**.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LicRcBehavior {
+    /// If set to false Licensebat will validate the dependencies no matter what file has been modified.
+    /// If set to true, validation will only happen when one of the dependency files or the .licrc files has been modified in the commit.
+    pub run_only_on_dependency_modification: Option<bool>,
+    /// If set to true, Licensebat will execute the check but it won't block the PR.
+    #[serde(default)]
+    pub do_not_block_pr: bool,
+    /// This will define the size of the buffer used to retrieve the dependencies.
+    /// It's set to 100 by default.
+    /// If you have a lot of dependencies, you might want to increase this value, but be careful, if the size is too big, the API might return an error.
+    pub retriever_buffer_size: Option<usize>,
+    /// Minimum confidence (from 0 to 1) needed to trust a license detected by analyzing the text of a license file.
+    /// It's set to [`DEFAULT_MIN_DETECTION_CONFIDENCE`] by default.
+    ///
+    /// Retrievers analyze the license file of a dependency when its registry doesn't declare an SPDX license.
+    /// If the detected license scores lower than this value, the dependency is still validated with it
+    /// but it's flagged for review: it will be reported as a warning, unless it's denied,
+    /// and its comment will tell the score so someone can check the license by hand.
+    pub min_detection_confidence: Option<f32>,
+    /// If set to true, Licensebat will not show the ignored dependencies in the final report.
+    #[serde(default)]
+    pub do_not_show_ignored_dependencies: bool,
+    /// If set to true, Licensebat will not show the dev dependencies in the final report.
+    #[serde(default)]
+    pub do_not_show_dev_dependencies: bool,
+    /// If set to true, Licensebat will not show the optional dependencies in the final report.
+    #[serde(default)]
+    pub do_not_show_optional_dependencies: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn licrc(accepted: Option<&[&str]>, unaccepted: Option<&[&str]>) -> LicRc {
+        let to_vec = |l: &[&str]| l.iter().map(ToString::to_string).collect();
+        LicRc {
+            licenses: LicRcLicenses {
+                accepted: accepted.map(to_vec),
+                unaccepted: unaccepted.map(to_vec),
+                ..LicRcLicenses::default()
+            },
+            ..LicRc::default()
+        }
+    }
+
+    fn dependency(licenses: &[&str]) -> RetrievedDependency {
+        RetrievedDependency::new(
+            "dep".to_owned(),
+            "1.0.0".to_owned(),
+            "rust".to_owned(),
+            None,
+            LicenseExpression::all_of(licenses.iter().copied()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn dual_licensed_dependency_is_valid_if_one_license_is_accepted() {
+        let licrc = licrc(Some(&["MIT"]), None);
+        let mut dep = dependency(&["MIT OR Apache-2.0"]);
+        licrc.validate(&mut dep);
+        assert!(dep.validated);
+        assert!(dep.is_valid);
+        assert!(dep.error.is_none());
+    }
+
+    #[test]
+    fn dependency_is_invalid_if_a_needed_license_is_not_accepted() {
+        let licrc = licrc(Some(&["MIT"]), None);
+        let mut dep = dependency(&["MIT AND Apache-2.0"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.error.as_deref(), Some("Not compliant"));
+    }
+
+    #[test]
+    fn any_of_declarations_are_valid_if_one_license_is_accepted() {
+        let licrc = licrc(Some(&["MIT"]), None);
+        let mut dep = dependency(&["GPL-3.0-only", "MIT"]);
+        dep.licenses = LicenseExpression::any_of(["GPL-3.0-only", "MIT"]);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.category, Some(Category::Permissive));
+
+        let mut dep = dependency(&["GPL-3.0-only", "MIT"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+    }
+
+    #[test]
+    fn overrides_by_version_take_precedence() {
+        let licrc = LicRc {
+            dependencies: LicRcDependencies {
+                overrides: Some(BTreeMap::from([
+                    ("foo".to_owned(), "MIT".to_owned()),
+                    ("foo@2.0.0".to_owned(), "Apache-2.0".to_owned()),
+                ])),
+                ..LicRcDependencies::default()
+            },
+            ..LicRc::default()
+        };
+        assert_eq!(
+            licrc.get_override(&Dependency::new("foo", "1.0.0")),
+            Some("MIT")
+        );
+        assert_eq!(
+            licrc.get_override(&Dependency::new("foo", "2.0.0")),
+            Some("Apache-2.0")
+        );
+        assert_eq!(licrc.get_override(&Dependency::new("bar", "2.0.0")), None);
+    }
+
+    #[test]
+    fn ignored_entries_support_patterns_and_versions() {
+        let licrc: LicRc = serde_json::from_value(serde_json::json!({
+            "licenses": {},
+            "dependencies": { "ignored": ["@acme/*", "openssl@<0.11"] },
+            "behavior": {},
+        }))
+        .unwrap();
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.name = "@acme/ui".to_owned();
+        assert!(licrc.is_ignored(&mut dep));
+        assert!(dep.is_ignored);
+
+        let mut dep = dependency(&["Apache-2.0"]);
+        dep.name = "openssl".to_owned();
+        dep.version = "0.10.55".to_owned();
+        assert!(licrc.is_ignored(&mut dep));
+
+        dep.version = "0.11.0".to_owned();
+        dep.is_ignored = false;
+        assert!(!licrc.is_ignored(&mut dep));
+
+        let mut licrc = licrc;
+        licrc.behavior.do_not_show_ignored_dependencies = true;
+        assert!(!licrc.filter_dependencies_before_retrieval(&Dependency::new("@acme/ui", "1.0.0")));
+        assert!(licrc.filter_dependencies_before_retrieval(&Dependency::new("openssl", "0.11.0")));
+    }
+
+    #[test]
+    fn categories_can_be_allowed_or_denied() {
+        let mut licrc = licrc(None, None);
+        licrc.licenses.allowed_categories =
+            Some(vec![Category::Permissive, Category::PublicDomain]);
+        licrc.licenses.denied_categories =
+            Some(vec![Category::StrongCopyleft, Category::NetworkCopyleft]);
+
+        let mut dep = dependency(&["BSD-3-Clause-Clear"]);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.category, Some(Category::Permissive));
+
+        let mut dep = dependency(&["GPL-3.0-only OR CC0-1.0"]);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.category, Some(Category::PublicDomain));
+
+        let mut dep = dependency(&["AGPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.category, Some(Category::NetworkCopyleft));
+
+        // not in the allowed categories
+        let mut dep = dependency(&["MPL-2.0"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.category, Some(Category::WeakCopyleft));
+    }
+
+    #[test]
+    fn explicit_licenses_take_precedence_over_categories() {
+        let mut licrc = licrc(Some(&["MPL-2.0"]), None);
+        licrc.licenses.allowed_categories = Some(vec![Category::Permissive]);
+        licrc.licenses.denied_categories = Some(vec![Category::WeakCopyleft]);
+        licrc.licenses.categories = Some(BTreeMap::from([(
+            "LicenseRef-Acme".to_owned(),
+            Category::Permissive,
+        )]));
+
+        assert!(licrc.licenses.is_accepted(&Expression::parse("MPL-2.0")));
+        assert!(licrc.licenses.is_accepted(&Expression::parse("MIT")));
+        assert!(licrc
+            .licenses
+            .is_accepted(&Expression::parse("LicenseRef-Acme")));
+        assert!(!licrc
+            .licenses
+            .is_accepted(&Expression::parse("LGPL-3.0-only")));
+        assert!(!licrc.licenses.is_accepted(&Expression::parse("MSC")));
+    }
+
+    #[test]
+    fn warned_licenses_do_not_invalidate_dependencies() {
+        let mut licrc = licrc(Some(&["MIT"]), None);
+        licrc.licenses.warned = Some(vec!["LGPL-3.0-only".to_owned()]);
+        licrc.licenses.warned_categories = Some(vec![Category::WeakCopyleft]);
+
+        let mut dep = dependency(&["LGPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.verdict, Verdict::Warn);
+        assert!(dep.comment.is_some());
+
+        let mut dep = dependency(&["MPL-2.0 OR MIT"]);
+        licrc.validate(&mut dep);
+        assert_eq!(dep.verdict, Verdict::Allow);
+
+        let mut dep = dependency(&["MPL-2.0 AND MIT"]);
+        licrc.validate(&mut dep);
+        assert_eq!(dep.verdict, Verdict::Warn);
+
+        let mut dep = dependency(&["MPL-2.0 AND GPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.verdict, Verdict::Deny);
+    }
+
+    #[test]
+    fn expired_exceptions_are_not_honoured() {
+        let licrc: LicRc = serde_json::from_value(serde_json::json!({
+            "dependencies": { "ignored": [
+                { "name": "foo", "reason": "Waiver", "approved_by": "legal", "expires": "2027-01-31" },
+            ] },
+        }))
+        .unwrap();
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.name = "foo".to_owned();
+        assert!(licrc.is_ignored_at(&mut dep, "2027-01-31".parse().unwrap()));
+        assert!(!dep.is_exception_expired);
+        assert_eq!(
+            dep.comment.unwrap().text,
+            "Ignored by the .licrc file: Waiver (approved by legal) - expires on 2027-01-31."
+        );
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.name = "foo".to_owned();
+        assert!(!licrc.is_ignored_at(&mut dep, "2027-02-01".parse().unwrap()));
+        assert!(!dep.is_ignored);
+        assert!(dep.is_exception_expired);
+        assert!(dep.comment.unwrap().text.contains("has expired"));
+    }
+
+    #[test]
+    fn dev_and_optional_dependencies_use_their_own_policy() {
+        let mut licrc = licrc(Some(&["MIT"]), None);
+        licrc.licenses.warned = Some(vec!["MPL-2.0".to_owned()]);
+        licrc.licenses.dev = Some(LicRcScopeLicenses {
+            unaccepted: Some(vec!["AGPL-3.0-only".to_owned()]),
+            ..LicRcScopeLicenses::default()
+        });
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Default));
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.is_dev = Some(true);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Dev));
+
+        let mut dep = dependency(&["MPL-2.0"]);
+        dep.is_dev = Some(true);
+        licrc.validate(&mut dep);
+        assert_eq!(dep.verdict, Verdict::Warn);
+
+        let mut dep = dependency(&["AGPL-3.0-only"]);
+        dep.is_dev = Some(true);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+
+        // there's no optional policy, so the main one is used
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.is_optional = Some(true);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Default));
+    }
+
+    #[test]
+    fn transitive_dependencies_use_their_own_policy() {
+        let mut licrc = licrc(Some(&["MIT"]), None);
+        licrc.licenses.transitive = Some(LicRcScopeLicenses {
+            accepted: Some(vec!["MIT".to_owned(), "LGPL-3.0-only".to_owned()]),
+            ..LicRcScopeLicenses::default()
+        });
+
+        let mut dep = dependency(&["LGPL-3.0-only"]);
+        dep.is_direct = Some(true);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Default));
+
+        let mut dep = dependency(&["LGPL-3.0-only"]);
+        dep.is_direct = Some(false);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Transitive));
+
+        // unknown dependencies use the main policy
+        let mut dep = dependency(&["LGPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.policy, Some(PolicyScope::Default));
+    }
+
+    #[test]
+    fn transitive_dependencies_can_be_ignored() {
+        let mut licrc = licrc(Some(&["MIT"]), None);
+        licrc.dependencies.ignore_transitive_dependencies = true;
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        dep.is_direct = Some(false);
+        licrc.validate(&mut dep);
+        assert!(dep.is_ignored);
+
+        let mut dep = dependency(&["GPL-3.0-only"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_ignored);
+        assert!(!dep.is_valid);
+
+        let transitive = Dependency {
+            is_direct: Some(false),
+            ..Dependency::new("foo", "1.0.0")
+        };
+        assert!(licrc.filter_dependencies_before_retrieval(&transitive));
+        licrc.behavior.do_not_show_ignored_dependencies = true;
+        assert!(!licrc.filter_dependencies_before_retrieval(&transitive));
+    }
+
+    #[test]
+    fn dependencies_flagged_for_review_are_warned_unless_denied() {
+        let licrc = licrc(Some(&["MIT"]), None);
+
+        let mut dep = dependency(&["MIT"]).flagged_for_review("Low score");
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+        assert_eq!(dep.verdict, Verdict::Warn);
+        assert_eq!(dep.comment.unwrap().text, "Low score");
+
+        let mut dep = dependency(&["GPL-3.0-only"]).flagged_for_review("Low score");
+        licrc.validate(&mut dep);
+        assert_eq!(dep.verdict, Verdict::Deny);
+    }
+
+    #[test]
+    fn unaccepted_licenses_can_be_avoided_with_or() {
+        let licrc = licrc(None, Some(&["GPL-3.0-only"]));
+        let mut dep = dependency(&["GPL-3.0-only OR MIT"]);
+        licrc.validate(&mut dep);
+        assert!(dep.is_valid);
+
+        let mut dep = dependency(&["GPL-3.0-only AND MIT"]);
+        licrc.validate(&mut dep);
+        assert!(!dep.is_valid);
+        assert_eq!(dep.failure_reason, Some(FailureReason::NotCompliant));
+    }
+
+    #[test]
+    fn or_later_licenses_are_only_denied_if_every_version_is_unaccepted() {
+        let is_valid = |unaccepted: &str, license: &str| {
+            let mut dep = dependency(&[license]);
+            licrc(None, Some(&[unaccepted])).validate(&mut dep);
+            dep.is_valid
+        };
+        assert!(is_valid("GPL-3.0-only", "GPL-2.0-or-later"));
+        assert!(is_valid("GPL-3.0-only", "GPL-2.0+"));
+        assert!(is_valid("LGPL-3.0", "LGPL-2.1+"));
+        assert!(!is_valid("GPL-2.0-or-later", "GPL-3.0+"));
+        assert!(!is_valid("GPL-2.0-only", "GPL-2.0"));
+    }
+}
@@ -0,0 +1,149 @@
+//! [Package URLs](https://github.com/package-url/purl-spec) identifying the dependencies.
+//!
+//! Collectors use the [`PackageUrl`] builder to describe where each dependency comes from,
+//! so it can be correlated with other tools (SBOMs, vulnerability databases...).
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::{collections::BTreeMap, fmt};
+
+/// Characters that are percent-encoded in the components of a [`PackageUrl`].
+///
+/// The spec forbids encoding the colon.
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~')
+    .remove(b':');
+
+/// Type of the Package URLs of Rust crates.
+pub const CARGO: &str = "cargo";
+/// Type of the Package URLs of npm packages.
+pub const NPM: &str = "npm";
+/// Type of the Package URLs of Dart packages.
+pub const PUB: &str = "pub";
+
+/// A [Package URL](https://github.com/package-url/purl-spec) (`pkg:type/namespace/name@version?qualifiers`).
+///
+/// Its string representation is canonical: components are percent-encoded and qualifiers are sorted.
+///
+/// # Example
+///
+/// ```
+/// use licensebat_core::purl::{PackageUrl, NPM};
+///
+/// let purl = PackageUrl::new(NPM, "@angular/core")
+///     .with_version("12.0.0")
+///     .with_qualifier("repository_url", "https://npm.acme.com");
+///
+/// assert_eq!(
+///     purl.to_string(),
+///     "pkg:npm/%40angular/core@12.0.0?repository_url=https:%2F%2Fnpm.acme.com"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageUrl {
+    ty: String,
+    namespace: Option<String>,
+    name: String,
+    version: Option<String>,
+    qualifiers: BTreeMap<String, String>,
+}
+
+impl PackageUrl {
+    /// Creates a new [`PackageUrl`] of the given type (`cargo`, `npm`, `pub`...).
+    ///
+    /// Names with a slash are split in namespace and name (i.e. npm scoped packages like `@angular/core`).
+    pub fn new(ty: impl Into<String>, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        let (namespace, name) = name
+            .rsplit_once('/')
+            .map_or((None, name), |(namespace, name)| (Some(namespace), name));
+        Self {
+            ty: ty.into().to_lowercase(),
+            namespace: namespace.map(str::to_owned),
+            name: name.to_owned(),
+            version: None,
+            qualifiers: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the version of the package.
+    #[must_use]
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Adds a qualifier (i.e. `repository_url` or `vcs_url`). Empty values are ignored.
+    #[must_use]
+    pub fn with_qualifier(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.qualifiers.insert(key.into().to_lowercase(), value);
+        }
+        self
+    }
+}
+
+impl fmt::Display for PackageUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encode = |component: &str| utf8_percent_encode(component, COMPONENT).to_string();
+        write!(f, "pkg:{}/", self.ty)?;
+        if let Some(namespace) = &self.namespace {
+            let segments = namespace
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .map(encode)
+                .collect::<Vec<_>>();
+            write!(f, "{}/", segments.join("/"))?;
+        }
+        write!(f, "{}", encode(&self.name))?;
+        if let Some(version) = &self.version {
+            write!(f, "@{}", encode(version))?;
+        }
+        if !self.qualifiers.is_empty() {
+            let qualifiers = self
+                .qualifiers
+                .iter()
+                .map(|(key, value)| format!("{key}={}", encode(value)))
+                .collect::<Vec<_>>();
+            write!(f, "?{}", qualifiers.join("&"))?;
+        }
+        Ok(())
+    }
+}
+
+impl From<PackageUrl> for String {
+    fn from(purl: PackageUrl) -> Self {
+        purl.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_canonical_package_urls() {
+        assert_eq!(
+            PackageUrl::new(CARGO, "serde")
+                .with_version("1.0.0+build")
+                .to_string(),
+            "pkg:cargo/serde@1.0.0%2Bbuild"
+        );
+        assert_eq!(
+            PackageUrl::new(PUB, "http")
+                .with_version("0.13.4")
+                .to_string(),
+            "pkg:pub/http@0.13.4"
+        );
+        assert_eq!(
+            PackageUrl::new(CARGO, "foo")
+                .with_version("0.1.0")
+                .with_qualifier("vcs_url", "git+https://github.com/acme/foo@abc")
+                .with_qualifier("repository_url", "")
+                .to_string(),
+            "pkg:cargo/foo@0.1.0?vcs_url=git%2Bhttps:%2F%2Fgithub.com%2Facme%2Ffoo%40abc"
+        );
+    }
+}
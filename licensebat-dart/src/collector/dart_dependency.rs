@@ -1,4 +1,7 @@
-use licensebat_core::Dependency;
+use licensebat_core::{
+    purl::{self, PackageUrl},
+    Dependency,
+};
 use serde::de::{Error, MapAccess, Visitor};
 use serde::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -16,16 +19,86 @@ pub struct DartDependency {
     pub is_optional: Option<bool>,
 }
 
+impl DartDependency {
+    /// Returns whether the dependency is a direct one (`direct main`, `direct dev` or `direct overridden`) or a `transitive` one.
+    pub fn is_direct(&self) -> Option<bool> {
+        if self.dependency.starts_with("direct") {
+            Some(true)
+        } else if self.dependency == "transitive" {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Describes where the dependency comes from (i.e. `hosted+https://pub.dev` or `git+https://github.com/acme/dep@main`).
+    pub fn source_description(&self) -> Option<String> {
+        if self.source.is_empty() {
+            return None;
+        }
+        let location = match self.source.as_str() {
+            "hosted" => self.description.url.clone(),
+            "git" => self
+                .description
+                .url
+                .as_ref()
+                .map(|url| format!("{url}{}", self.git_reference())),
+            "path" => self.description.path.clone(),
+            "sdk" => self.description.name.clone(),
+            _ => None,
+        };
+        Some(location.map_or_else(
+            || self.source.clone(),
+            |location| format!("{}+{location}", self.source),
+        ))
+    }
+
+    /// Builds the Package URL of the dependency.
+    pub fn package_url(&self) -> Option<String> {
+        let name = self.description.name.as_deref()?;
+        let purl = PackageUrl::new(purl::PUB, name).with_version(&self.version);
+        let purl = match (self.source.as_str(), &self.description.url) {
+            ("hosted", Some(url)) if !DEFAULT_HOSTS.contains(&url.trim_end_matches('/')) => {
+                purl.with_qualifier("repository_url", url)
+            }
+            ("git", Some(url)) => {
+                purl.with_qualifier("vcs_url", format!("git+{url}{}", self.git_reference()))
+            }
+            _ => purl,
+        };
+        Some(purl.into())
+    }
+
+    /// Gets the git reference (`@ref`) of the dependency, preferring the resolved one.
+    fn git_reference(&self) -> String {
+        self.description
+            .resolved_reference
+            .as_ref()
+            .or(self.description.reference.as_ref())
+            .map(|reference| format!("@{reference}"))
+            .unwrap_or_default()
+    }
+}
+
+/// Hosts whose packages don't need a `repository_url` in their Package URL.
+const DEFAULT_HOSTS: [&str; 2] = ["https://pub.dartlang.org", "https://pub.dev"];
+
 impl TryInto<Dependency> for DartDependency {
     type Error = String;
 
     fn try_into(self) -> Result<Dependency, Self::Error> {
+        let is_direct = self.is_direct();
+        let source = self.source_description();
+        let purl = self.package_url();
         let name = self
             .description
             .name
             .ok_or_else(|| "No name found in Dart dependency".to_string())?;
 
         Ok(Dependency {
+            is_direct,
+            source,
+            purl,
             name,
             version: self.version,
             is_dev: self.is_dev,
@@ -36,13 +109,16 @@ impl TryInto<Dependency> for DartDependency {
 
 impl From<&DartDependency> for Dependency {
     fn from(dependency: &DartDependency) -> Self {
-        let name = dependency.description.name.clone().unwrap_or(String::new());
+        let name = dependency.description.name.clone().unwrap_or_default();
 
         Dependency {
             name,
             version: dependency.version.clone(),
             is_dev: dependency.is_dev,
             is_optional: dependency.is_optional,
+            is_direct: dependency.is_direct(),
+            source: dependency.source_description(),
+            purl: dependency.package_url(),
         }
     }
 }
@@ -53,6 +129,8 @@ pub struct Description {
     pub path: Option<String>,
     #[serde(default, rename = "ref")]
     pub reference: Option<String>,
+    #[serde(default, rename = "resolved-ref")]
+    pub resolved_reference: Option<String>,
     #[serde(default)]
     pub url: Option<String>,
     #[serde(default)]
@@ -113,6 +191,7 @@ impl<'de> Visitor<'de> for DescriptionVisitor {
                 "path" => description.path = Some(value),
                 "name" => description.name = Some(value),
                 "ref" => description.reference = Some(value),
+                "resolved-ref" => description.resolved_reference = Some(value),
                 "url" => description.url = Some(value),
                 _ => (),
             }
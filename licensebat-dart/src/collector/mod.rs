@@ -13,11 +13,12 @@ mod dart_dependency;
 
 use self::dart_dependency::{DartDependencies, DartDependency};
 use crate::retriever::{self, hosted::Retriever};
-use futures::prelude::*;
 use licensebat_core::{
-    collector::{RetrievedDependencyStream, RetrievedDependencyStreamResult},
+    collector::{Error, RetrievedDependencyStream, RetrievedDependencyStreamResult},
+    license::LicenseExpression,
     licrc::LicRc,
-    Collector, Comment, Dependency, FileCollector, RetrievedDependency,
+    Collector, Comment, Dependency, DependencyGraph, Detector, FailureReason, FileCollector,
+    LicenseSource, Provenance, RetrievedDependency,
 };
 use std::sync::Arc;
 use tracing::instrument;
@@ -77,18 +78,69 @@ impl<R: Retriever> FileCollector for Dart<R> {
         &self,
         dependency_file_content: &str,
         licrc: &LicRc,
-    ) -> RetrievedDependencyStreamResult {
-        let dependencies = serde_yaml::from_str::<DartDependencies>(dependency_file_content)?
-            .into_vec_collection();
+    ) -> RetrievedDependencyStreamResult<'_> {
+        let dependencies = parse_dependencies(dependency_file_content)?;
 
-        let futures = dependencies
+        let dependencies = dependencies
             .into_iter()
-            .filter(|dep| licrc.filter_dependencies_before_retrieval(&dep.into()))
-            .map(|dep| get_dependency(dep, &self.retriever).boxed())
-            .collect();
+            .map(|dep| {
+                let dependency = Dependency::from(&dep);
+                (dep, dependency)
+            })
+            .filter(|(_, dependency)| licrc.filter_dependencies_before_retrieval(dependency));
 
-        Ok(RetrievedDependencyStream::new(futures))
+        let retriever = &self.retriever;
+        Ok(RetrievedDependencyStream::retrieve(
+            dependencies,
+            licrc,
+            crate::DART,
+            |dependency| {
+                Some(format!(
+                    "https://pub.dev/packages/{}/versions/{}",
+                    dependency.name, dependency.version
+                ))
+            },
+            move |dep| get_dependency(dep, retriever),
+        ))
     }
+
+    /// Builds the [`DependencyGraph`] of the `pubspec.lock` file.
+    ///
+    /// `pubspec.lock` files don't contain the dependencies of each package, only whether they are direct or transitive,
+    /// so the graph will have no edges nor roots.
+    #[instrument(skip(self, dependency_file_content), level = "debug")]
+    fn get_dependency_graph(
+        &self,
+        dependency_file_content: &str,
+    ) -> Result<DependencyGraph, Error> {
+        let mut dependencies = parse_dependencies(dependency_file_content)?;
+        dependencies.sort_by(|a, b| a.description.name.cmp(&b.description.name));
+        let mut graph = DependencyGraph::new();
+        for dependency in dependencies {
+            let is_direct = dependency.is_direct();
+            let id = graph.add_node(
+                dependency.description.name.unwrap_or_default(),
+                dependency.version,
+            );
+            if is_direct == Some(true) {
+                graph.mark_direct(id);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+fn parse_dependencies(dependency_file_content: &str) -> Result<Vec<DartDependency>, Error> {
+    serde_yaml::from_str::<DartDependencies>(dependency_file_content)
+        .map(DartDependencies::into_vec_collection)
+        .map_err(|e| {
+            let location = e.location();
+            let error = Error::parse(crate::DART, e);
+            match location {
+                Some(location) => error.with_position(location.line(), location.column()),
+                None => error,
+            }
+        })
 }
 
 /// Gets a dependency from Dart Pub.
@@ -116,12 +168,18 @@ async fn get_dependency<R: Retriever>(
 fn resolve_sdk_dependency(dependency: &DartDependency) -> RetrievedDependency {
     retrieved_dependency(
         dependency,
-        Some(vec!["BSD-3-Clause".to_owned()]),
+        Some(LicenseExpression::license("BSD-3-Clause")),
         None,
         Some("https://github.com/flutter/flutter".to_string()),
         Some(Comment::removable("SDK dependency. **You should accept this dependency**. Consider adding **BSD-3-Clause** to the **.licrc** configuration file.")),
         None,
     )
+    .with_provenance(Provenance {
+        source: LicenseSource::Default,
+        location: Some("https://dart.dev/tools/pub/publishing#preparing-to-publish".to_string()),
+        detector: Detector::Assumed,
+        confidence: 1.0,
+    })
 }
 
 /// Resolves the license by scrapping the Dart pub website and then the license in github if available.
@@ -145,6 +203,7 @@ async fn resolve_hosted_dependency<R: Retriever>(
                 None,
                 None,
             )
+            .with_failure_reason(FailureReason::NetworkError)
         })
     } else {
         retrieved_dependency(
@@ -155,6 +214,7 @@ async fn resolve_hosted_dependency<R: Retriever>(
             None,
             None,
         )
+        .with_failure_reason(FailureReason::ParseError)
     }
 }
 
@@ -170,9 +230,10 @@ fn resolve_git_dependency(dependency: &DartDependency) -> RetrievedDependency {
         None,
         Some("Git source is not supported".to_string()),
         dependency.description.url.clone(),
-        Some(Comment::removable("Git projects are not supported yet. We're working on it but there are too many different git hosting providers and supporting private repos is hard. We're marking this as **invalid by default** so you check for yourself the validity of the license. Consider **adding this dependency to the ignored list** in the **.licrc** configuration file if you trust the source.")),
+        Some(Comment::removable("Git projects are not supported yet. We're working on it but there are too many different git hosting providers and supporting private repos is hard. We're marking this as **invalid by default** so you check for yourself the validity of the license. Consider **declaring its license in the overrides** or **adding this dependency to the ignored list** in the **.licrc** configuration file if you trust the source.")),
         None,
     )
+    .with_failure_reason(FailureReason::UnsupportedSource)
 }
 
 /// Resolves to invalid dependency as we don't support this type for the moment.
@@ -185,12 +246,13 @@ fn resolve_unknown_dependency(dependency: &DartDependency) -> RetrievedDependenc
         None,
         None,
     )
+    .with_failure_reason(FailureReason::UnsupportedSource)
 }
 
 /// Builds a `RetrievedDependency`
 fn retrieved_dependency(
     dependency: &DartDependency,
-    licenses: Option<Vec<String>>,
+    licenses: Option<LicenseExpression>,
     error: Option<String>,
     url: Option<String>,
     comment: Option<Comment>,
@@ -234,6 +296,7 @@ mod tests {
             description: Description {
                 path: None,
                 reference: None,
+                resolved_reference: None,
                 url: None,
                 name: Some(dependency_name.to_string()),
             },
@@ -247,4 +310,111 @@ mod tests {
         assert_eq!(res.name, dependency_name);
         assert!(res.licenses.is_some());
     }
+
+    fn dependency(source: &str) -> DartDependency {
+        DartDependency {
+            version: "1.0.0".to_string(),
+            source: source.to_string(),
+            dependency: "direct main".to_string(),
+            description: Description {
+                path: None,
+                reference: None,
+                resolved_reference: None,
+                url: Some("https://github.com/acme/dep".to_string()),
+                name: Some("dep".to_string()),
+            },
+            is_dev: None,
+            is_optional: None,
+        }
+    }
+
+    #[test]
+    fn unsupported_sources_have_a_failure_reason() {
+        let git = resolve_git_dependency(&dependency("git"));
+        assert!(!git.is_valid);
+        assert_eq!(git.failure_reason, Some(FailureReason::UnsupportedSource));
+
+        let unknown = resolve_unknown_dependency(&dependency("path"));
+        assert_eq!(
+            unknown.failure_reason,
+            Some(FailureReason::UnsupportedSource)
+        );
+
+        let sdk = resolve_sdk_dependency(&dependency("sdk"));
+        assert_eq!(sdk.failure_reason, None);
+        assert_eq!(sdk.provenance.unwrap().detector, Detector::Assumed);
+    }
+
+    #[test]
+    fn dependencies_have_a_source_and_a_package_url() {
+        assert_eq!(
+            dependency("git").source_description().as_deref(),
+            Some("git+https://github.com/acme/dep")
+        );
+        assert_eq!(
+            dependency("git").package_url().as_deref(),
+            Some("pkg:pub/dep@1.0.0?vcs_url=git%2Bhttps:%2F%2Fgithub.com%2Facme%2Fdep")
+        );
+        assert_eq!(
+            dependency("hosted").package_url().as_deref(),
+            Some("pkg:pub/dep@1.0.0?repository_url=https:%2F%2Fgithub.com%2Facme%2Fdep")
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_their_position() {
+        let dart = Dart::default();
+        let error = dart
+            .get_dependencies("packages:\n  dep: [", &LicRc::default())
+            .err()
+            .unwrap();
+
+        assert!(matches!(error, Error::Parse { ref collector, .. } if collector == "dart"));
+        assert!(error.position().is_some());
+    }
+
+    #[test]
+    fn it_builds_the_dependency_graph() {
+        let content = r#"
+packages:
+  http:
+    dependency: "direct main"
+    description:
+      name: http
+      url: "https://pub.dartlang.org"
+    source: hosted
+    version: "0.13.4"
+  http_parser:
+    dependency: transitive
+    description:
+      name: http_parser
+      url: "https://pub.dartlang.org"
+    source: hosted
+    version: "4.0.0"
+  test:
+    dependency: "direct dev"
+    description:
+      name: test
+      url: "https://pub.dartlang.org"
+    source: hosted
+    version: "1.21.1"
+"#;
+
+        let graph = Dart::default().get_dependency_graph(content).unwrap();
+        let direct = graph
+            .direct_dependencies()
+            .map(|id| graph.node(id).unwrap().name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(graph.nodes().len(), 3);
+        assert_eq!(direct, vec!["http", "test"]);
+        assert!(graph.edges().is_empty());
+
+        let transitive = parse_dependencies(content)
+            .unwrap()
+            .into_iter()
+            .find(|dep| dep.description.name.as_deref() == Some("http_parser"))
+            .unwrap();
+        assert_eq!(Dependency::from(&transitive).is_direct, Some(false));
+    }
 }
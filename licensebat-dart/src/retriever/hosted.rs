@@ -9,13 +9,20 @@
 
 use askalono::{Store, TextData};
 use futures::{future::BoxFuture, Future, FutureExt, TryFutureExt};
-use licensebat_core::{Comment, Dependency, RetrievedDependency};
-use reqwest::Client;
+use licensebat_core::{
+    license::{LicenseExpression, Normalizer},
+    licrc::DEFAULT_MIN_DETECTION_CONFIDENCE,
+    Comment, Dependency, FailureReason, LicenseSource, Provenance, RetrievedDependency,
+};
+use reqwest::{Client, StatusCode};
 use scraper::Element;
 use scraper::{ElementRef, Html, Selector};
 use std::{sync::Arc, vec};
 use tracing::instrument;
 
+/// Confidence given to the generic licenses declared by pub.dev, as they may be imprecise (i.e. `BSD`).
+const GENERIC_LICENSE_CONFIDENCE: f32 = 0.5;
+
 /// Trait used by the [`Hosted`] struct to retrieve dependencies.
 pub trait Retriever: Send + Sync + std::fmt::Debug {
     /// The associated error which can be returned.
@@ -38,6 +45,7 @@ pub trait Retriever: Send + Sync + std::fmt::Debug {
 pub struct Hosted {
     client: Client,
     store: Arc<Option<Store>>,
+    min_detection_confidence: f32,
 }
 
 impl Hosted {
@@ -45,7 +53,23 @@ impl Hosted {
     /// If you want to reuse a [`reqwest::Client`] pool consider using the [`Hosted::new`] method.
     #[must_use]
     pub fn new(client: Client, store: Arc<Option<Store>>) -> Self {
-        Self { client, store }
+        Self {
+            client,
+            store,
+            min_detection_confidence: DEFAULT_MIN_DETECTION_CONFIDENCE,
+        }
+    }
+
+    /// Sets the minimum confidence needed to trust a license detected by analyzing the text of a license file,
+    /// as explained in [`LicRcBehavior::min_detection_confidence`].
+    ///
+    /// It's set to [`DEFAULT_MIN_DETECTION_CONFIDENCE`] by default.
+    ///
+    /// [`LicRcBehavior::min_detection_confidence`]: licensebat_core::licrc::LicRcBehavior::min_detection_confidence
+    #[must_use]
+    pub const fn with_min_detection_confidence(mut self, min_detection_confidence: f32) -> Self {
+        self.min_detection_confidence = min_detection_confidence;
+        self
     }
 }
 
@@ -62,6 +86,7 @@ impl Clone for Hosted {
         Self {
             client: self.client.clone(),
             store: self.store.clone(),
+            min_detection_confidence: self.min_detection_confidence,
         }
     }
 }
@@ -78,6 +103,7 @@ impl std::fmt::Debug for Hosted {
                     &"None"
                 },
             )
+            .field("min_detection_confidence", &self.min_detection_confidence)
             .finish()
     }
 }
@@ -94,14 +120,23 @@ impl Retriever for Hosted {
         );
 
         let store = self.store.clone();
+        let min_detection_confidence = self.min_detection_confidence;
 
         self.client
             .get(format!("{url}/license"))
             .send()
-            .and_then(reqwest::Response::text)
-            .map(move |html| {
-                html.map(|html| {
+            .and_then(|response| {
+                let status = response.status();
+                response.text().map_ok(move |html| (status, html))
+            })
+            .map(move |response| {
+                response.map(|(status, html)| {
                     let url = url.clone();
+                    let license_url = format!("{url}/license");
+                    if status == StatusCode::NOT_FOUND {
+                        return retrieved_dependency(&dependency, None, Some("Package version not found in pub.dev".to_owned()), Some(url), None, None)
+                            .with_failure_reason(FailureReason::NotFound);
+                    }
                     // scrape the html looking for the license
                     let document = Html::parse_document(&html);
                     let declared_license = Selector::parse(r#"h3[class="title"]"#).ok()
@@ -125,7 +160,7 @@ impl Retriever for Hosted {
                         });
                     }
 
-                    let declared_licenses = declared_license.clone().map(|x| vec![x]);
+                    let declared_licenses = declared_license.clone().map(LicenseExpression::License);
 
                     if let (Some(official_license), Some(store)) = (official_license, store.as_ref()) {
                         // Some licenses, like BSD are represented in an imprecise way in pub dev,
@@ -134,7 +169,8 @@ impl Retriever for Hosted {
                         // so we'll short circuit these ones.
                         #[allow(clippy::single_match_else)]
                         match declared_license.as_deref() {
-                            Some("MIT") => retrieved_dependency(&dependency, declared_licenses, None, Some(url), None, None),
+                            Some("MIT") => retrieved_dependency(&dependency, declared_licenses, None, Some(url), None, None)
+                                .with_provenance(Provenance::declared(LicenseSource::Registry, Some(license_url))),
                             _ => {
                                 let result = store.analyze(&TextData::from(official_license.as_str()));
                                 tracing::debug!(
@@ -142,9 +178,10 @@ impl Retriever for Hosted {
                                     result.score,
                                     result.name
                                 );
-                                // TODO:  MAGIC NUMBER HERE! THIS SHOULD BE CONFIGURABLE
-                                let (license, comment) = if result.score >= 0.8 {
-                                    let comment = if Some(result.name.replace('-', " ")) == declared_license {
+                                let is_confident = result.score >= min_detection_confidence;
+                                let (license, comment, provenance) = if is_confident {
+                                    let declared_spdx_license = declared_license.as_deref().map(|l| Normalizer::default().normalize(l));
+                                    let comment = if declared_spdx_license.as_deref() == Some(result.name) {
                                         None
                                     } else {
                                         let comment = format!(
@@ -155,28 +192,39 @@ impl Retriever for Hosted {
                                         );
                                         Some(comment)
                                     };
-                                    (Some(result.name.to_string()), comment)
+                                    (Some(result.name.to_string()), comment, Some(Provenance::askalono(license_url, result.score)))
                                 } else {
                                     let comment = format!(
-                                        "Using **Pub Dev Generic License**. Our analysis, though, estimated that it could be **{}** with a **{:.2}%** score.",
+                                        "Using **Pub Dev Generic License**. Our analysis, though, estimated that it could be **{}** with a **{:.2}%** score, lower than the minimum detection confidence (**{:.2}%**). Please review it.",
                                         result.name,
-                                        result.score * 100.0
+                                        result.score * 100.0,
+                                        min_detection_confidence * 100.0
                                     );
-                                    (declared_license.clone(), Some(comment))
+                                    let provenance = generic_provenance(declared_license.as_deref(), license_url);
+                                    (declared_license.clone(), Some(comment), provenance)
                                 };
 
-                                retrieved_dependency(
+                                // the comment of a dependency flagged for review is added when flagging it
+                                let (comment, review) = if is_confident { (comment, None) } else { (None, comment) };
+                                let mut retrieved = retrieved_dependency(
                                     &dependency,
-                                    license.map(|l| vec![l]),
+                                    license.map(LicenseExpression::License),
                                     None,
                                     Some(url),
                                     comment.map(Comment::non_removable),
                                     Some(vec![(result.name.to_string(), result.score)])
-                                )
+                                );
+                                retrieved.provenance = provenance;
+                                match review {
+                                    Some(review) => retrieved.flagged_for_review(review),
+                                    None => retrieved,
+                                }
                             }
                         }
                     } else {
-                        retrieved_dependency(&dependency, declared_licenses, None, Some(url), Some(Comment::removable("Using **Pub Dev Generic License**. We couldn't get the original license.")), None)
+                        let mut retrieved = retrieved_dependency(&dependency, declared_licenses, None, Some(url), Some(Comment::removable("Using **Pub Dev Generic License**. We couldn't get the original license.")), None);
+                        retrieved.provenance = generic_provenance(declared_license.as_deref(), license_url);
+                        retrieved
                     }
                 })
             }).boxed()
@@ -185,7 +233,7 @@ impl Retriever for Hosted {
 
 fn retrieved_dependency(
     dependency: &Dependency,
-    licenses: Option<Vec<String>>,
+    licenses: Option<LicenseExpression>,
     error: Option<String>,
     url: Option<String>,
     comment: Option<Comment>,
@@ -205,6 +253,14 @@ fn retrieved_dependency(
     )
 }
 
+/// Returns the [`Provenance`] of the generic license declared by pub.dev, if any.
+fn generic_provenance(declared_license: Option<&str>, license_url: String) -> Option<Provenance> {
+    declared_license.map(|_| Provenance {
+        confidence: GENERIC_LICENSE_CONFIDENCE,
+        ..Provenance::declared(LicenseSource::Registry, Some(license_url))
+    })
+}
+
 /// Returns the imprecise license that pub.dev provides
 fn get_imprecise_license(sibling: &ElementRef) -> Option<String> {
     let lic = sibling.inner_html();
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod integration_hosted_retriever {
     use askalono::Store;
-    use licensebat_core::Dependency;
+    use licensebat_core::{license::LicenseExpression, Dependency};
     use licensebat_dart::retriever::{self, hosted::Retriever};
     use std::sync::Arc;
 
@@ -20,7 +20,7 @@ mod integration_hosted_retriever {
             .get_dependency(Dependency::new("flare_dart", "2.3.3"))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert!(dep.comment.is_none());
         assert_eq!(dep.name, "flare_dart");
         assert_eq!(&dep.dependency_type, licensebat_dart::DART);
@@ -37,7 +37,10 @@ mod integration_hosted_retriever {
             ))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["BSD-3-Clause".to_string()]), dep.licenses);
+        assert_eq!(
+            Some(LicenseExpression::license("BSD-3-Clause")),
+            dep.licenses
+        );
         assert!(dep.comment.is_some());
         assert_eq!(dep.name, "flutter_local_notifications_platform_interface");
         assert_eq!(&dep.dependency_type, licensebat_dart::DART);
@@ -51,7 +54,10 @@ mod integration_hosted_retriever {
             .get_dependency(Dependency::new("file", "6.0.0-nullsafety.2"))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["BSD-3-Clause".to_string()]), dep.licenses);
+        assert_eq!(
+            Some(LicenseExpression::license("BSD-3-Clause")),
+            dep.licenses
+        );
         assert!(dep.comment.is_some());
         assert_eq!(dep.name, "file");
         assert_eq!(&dep.dependency_type, licensebat_dart::DART);
@@ -67,7 +73,7 @@ mod integration_hosted_retriever {
             .get_dependency(Dependency::new("fake_async", "1.2.0-nullsafety.1"))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["Apache-2.0".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("Apache-2.0")), dep.licenses);
         assert_eq!(
             dep.comment.map(|x| x.text.contains("Our score for")),
             Some(true)
@@ -89,7 +95,7 @@ mod integration_hosted_retriever {
             .get_dependency(Dependency::new("random_color", "1.0.3"))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(
             dep.comment.map(|x| x.text.contains("Our score for")),
             Some(true)
@@ -131,7 +137,7 @@ mod integration_hosted_retriever {
             .get_dependency(Dependency::new("flutter_isolate", "1.0.0+14"))
             .await
             .unwrap();
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert!(dep.comment.is_some());
         assert_eq!(dep.name, "flutter_isolate");
         assert_eq!(&dep.dependency_type, licensebat_dart::DART);
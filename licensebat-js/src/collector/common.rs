@@ -1,21 +1,89 @@
-use crate::retriever::npm::Retriever;
-use futures::FutureExt;
-use licensebat_core::{collector::RetrievedDependencyStream, Dependency};
+use crate::retriever::npm::{npm_url, Retriever};
+use licensebat_core::{
+    collector::RetrievedDependencyStream,
+    licrc::LicRc,
+    purl::{self, PackageUrl},
+    Dependency,
+};
 use tracing::instrument;
 
 /// String used to identify the type of dependency
 pub const NPM: &str = "npm";
 
-#[instrument(skip(deps, retriever))]
-pub fn retrieve_from_npm<'a, I, R>(deps: I, retriever: &R) -> RetrievedDependencyStream<'a>
+/// Registries whose packages don't need a `repository_url` in their Package URL.
+const DEFAULT_REGISTRIES: [&str; 2] =
+    ["https://registry.npmjs.org", "https://registry.yarnpkg.com"];
+
+/// Builds the Package URL of an npm package from the url it has been resolved to, if known.
+pub fn package_url(name: &str, version: &str, resolved: Option<&str>) -> String {
+    let purl = PackageUrl::new(purl::NPM, name).with_version(version);
+    let Some(resolved) = resolved else {
+        return purl.into();
+    };
+    if resolved.starts_with("git") {
+        purl.with_qualifier("vcs_url", resolved)
+    } else if let Some((registry, _)) = resolved.split_once(&format!("/{name}/-/")) {
+        if DEFAULT_REGISTRIES.contains(&registry) {
+            purl
+        } else {
+            purl.with_qualifier("repository_url", registry)
+        }
+    } else {
+        purl.with_qualifier("download_url", resolved)
+    }
+    .into()
+}
+
+/// Retrieves the dependencies from npm, unless their license has been overridden in the [`LicRc`].
+#[instrument(skip(deps, retriever, licrc))]
+pub fn retrieve_from_npm<'a, I, R>(
+    deps: I,
+    retriever: &'a R,
+    licrc: &LicRc,
+) -> RetrievedDependencyStream<'a>
 where
     I: Iterator<Item = Dependency>,
     R: Retriever + 'a,
 {
-    let iter = deps
-        .into_iter()
-        .map(|dep| retriever.get_dependency(dep).boxed())
-        .collect();
+    RetrievedDependencyStream::retrieve(
+        deps.map(|dep| (dep.clone(), dep)),
+        licrc,
+        NPM,
+        |dep| Some(npm_url(dep)),
+        move |dep| retriever.get_dependency(dep),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    RetrievedDependencyStream::new(iter)
+    #[test]
+    fn package_urls_describe_non_default_registries() {
+        assert_eq!(
+            package_url(
+                "@babel/core",
+                "7.0.0",
+                Some("https://registry.npmjs.org/@babel/core/-/core-7.0.0.tgz")
+            ),
+            "pkg:npm/%40babel/core@7.0.0"
+        );
+        assert_eq!(
+            package_url(
+                "foo",
+                "1.0.0",
+                Some("https://npm.acme.com/foo/-/foo-1.0.0.tgz")
+            ),
+            "pkg:npm/foo@1.0.0?repository_url=https:%2F%2Fnpm.acme.com"
+        );
+        assert_eq!(
+            package_url(
+                "foo",
+                "1.0.0",
+                Some("git+ssh://git@github.com/acme/foo.git#abc")
+            ),
+            "pkg:npm/foo@1.0.0?vcs_url=git%2Bssh:%2F%2Fgit%40github.com%2Facme%2Ffoo.git%23abc"
+        );
+        assert_eq!(package_url("foo", "1.0.0", None), "pkg:npm/foo@1.0.0");
+    }
 }
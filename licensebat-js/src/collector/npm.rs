@@ -1,10 +1,15 @@
 use crate::{
-    collector::common::{retrieve_from_npm, NPM},
+    collector::common::{package_url, retrieve_from_npm, NPM},
     retriever::{self, npm::Retriever},
 };
 use licensebat_core::{
-    collector::RetrievedDependencyStreamResult, licrc::LicRc, Collector, Dependency, FileCollector,
+    collector::{Error, RetrievedDependencyStreamResult},
+    graph::NodeId,
+    licrc::LicRc,
+    Collector, Dependency, DependencyGraph, FileCollector,
 };
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use tracing::instrument;
 
 /// NPM dependency [`FileCollector`] generic over [`Retriever`].
@@ -41,23 +46,360 @@ impl<R: Retriever> FileCollector for Npm<R> {
         String::from("package-lock.json")
     }
 
+    fn get_dependency_file_patterns(&self) -> Vec<String> {
+        // npm-shrinkwrap.json has the same format as package-lock.json
+        vec![
+            self.get_dependency_filename(),
+            String::from("npm-shrinkwrap.json"),
+        ]
+    }
+
     #[instrument(skip(self))]
     fn get_dependencies(
         &self,
         dependency_file_content: &str,
         licrc: &LicRc,
-    ) -> RetrievedDependencyStreamResult {
-        let npm_deps = package_lock_json_parser::parse_dependencies(dependency_file_content)?
+    ) -> RetrievedDependencyStreamResult<'_> {
+        let lock = PackageLock::parse(&self.get_name(), dependency_file_content)?;
+        let graph = lock.graph();
+        let sources = lock.sources();
+        let npm_deps = package_lock_json_parser::parse_dependencies(dependency_file_content)
+            .map_err(|e| parse_error(&self.get_name(), e))?
             .into_iter()
-            .map(|dep| Dependency {
-                // TODO: for yarn, this key includes the version (as there can be more than one version of a package declared)
-                name: dep.name,
-                version: dep.version,
-                is_dev: Some(dep.is_dev),
-                is_optional: Some(dep.is_optional),
+            .map(|dep| {
+                // lockfiles v3 only have packages, whose keys are their paths (node_modules/name)
+                let name = dep
+                    .name
+                    .rsplit_once("node_modules/")
+                    .map_or(dep.name.as_str(), |(_, name)| name)
+                    .to_owned();
+                let source = sources
+                    .get(&(name.as_str(), dep.version.as_str()))
+                    .map(|resolved| (*resolved).to_owned());
+                Dependency {
+                    is_direct: lock.is_direct(&graph, &name, &dep.version),
+                    purl: Some(package_url(&name, &dep.version, source.as_deref())),
+                    source,
+                    name,
+                    version: dep.version,
+                    is_dev: Some(dep.is_dev),
+                    is_optional: Some(dep.is_optional),
+                }
             })
             .filter(|dep| licrc.filter_dependencies_before_retrieval(dep));
 
-        Ok(retrieve_from_npm(npm_deps, &self.retriever))
+        Ok(retrieve_from_npm(npm_deps, &self.retriever, licrc))
+    }
+
+    /// Builds the [`DependencyGraph`] of the `package-lock.json` file.
+    ///
+    /// Lockfiles v1 don't declare the dependencies of the project,
+    /// so the top-level packages no other package depends on are considered its direct dependencies.
+    #[instrument(skip(self, dependency_file_content))]
+    fn get_dependency_graph(
+        &self,
+        dependency_file_content: &str,
+    ) -> Result<DependencyGraph, Error> {
+        PackageLock::parse(&self.get_name(), dependency_file_content).map(|lock| lock.graph())
+    }
+}
+
+/// Minimal representation of a `package-lock.json` file, only used to know the structure of its dependencies.
+#[derive(Deserialize)]
+struct PackageLock {
+    name: Option<String>,
+    version: Option<String>,
+    /// Packages by their path (lockfiles v2 and v3).
+    #[serde(default)]
+    packages: BTreeMap<String, LockPackage>,
+    /// Nested dependencies (lockfiles v1 and v2).
+    #[serde(default)]
+    dependencies: BTreeMap<String, LockDependency>,
+}
+
+impl PackageLock {
+    fn parse(collector: &str, dependency_file_content: &str) -> Result<Self, Error> {
+        serde_json::from_str(dependency_file_content).map_err(|e| json_parse_error(collector, e))
+    }
+
+    fn is_v1(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    fn graph(&self) -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        let root_version = self
+            .version
+            .as_deref()
+            .or_else(|| self.packages.get("").and_then(|p| p.version.as_deref()))
+            .unwrap_or_default();
+        let root = graph.add_node(self.name.as_deref().unwrap_or_default(), root_version);
+        graph.mark_root(root);
+
+        if self.is_v1() {
+            add_v1_dependencies(&mut graph, &mut vec![], &self.dependencies);
+            for (name, dependency) in &self.dependencies {
+                let id = graph.add_node(name.as_str(), dependency.version.as_str());
+                if graph.dependents_of(id).next().is_none() {
+                    graph.add_edge(root, id);
+                }
+            }
+        } else {
+            add_v2_packages(&mut graph, root, &self.packages);
+        }
+
+        graph.mark_direct_dependencies_of_roots();
+        graph
+    }
+
+    /// Returns the urls the packages have been resolved to by their name and version.
+    fn sources(&self) -> HashMap<(&str, &str), &str> {
+        fn add_v1<'a>(
+            sources: &mut HashMap<(&'a str, &'a str), &'a str>,
+            dependencies: &'a BTreeMap<String, LockDependency>,
+        ) {
+            for (name, dependency) in dependencies {
+                if let Some(resolved) = &dependency.resolved {
+                    sources.insert((name.as_str(), dependency.version.as_str()), resolved);
+                }
+                add_v1(sources, &dependency.dependencies);
+            }
+        }
+
+        let mut sources = HashMap::new();
+        for (path, package) in &self.packages {
+            if let (Some(version), Some(resolved)) = (&package.version, &package.resolved) {
+                sources.insert(
+                    (package_name(path, package), version.as_str()),
+                    resolved.as_str(),
+                );
+            }
+        }
+        add_v1(&mut sources, &self.dependencies);
+        sources
+    }
+
+    /// Returns whether a dependency is direct according to the graph of the lockfile.
+    ///
+    /// In lockfiles v1, top-level packages other packages depend on may be direct dependencies too, so we can't know it.
+    fn is_direct(&self, graph: &DependencyGraph, name: &str, version: &str) -> Option<bool> {
+        match graph.is_direct(name, version) {
+            Some(false)
+                if self.is_v1()
+                    && self
+                        .dependencies
+                        .get(name)
+                        .is_some_and(|dependency| dependency.version == version) =>
+            {
+                None
+            }
+            is_direct => is_direct,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LockPackage {
+    name: Option<String>,
+    version: Option<String>,
+    resolved: Option<String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct LockDependency {
+    version: String,
+    resolved: Option<String>,
+    #[serde(default)]
+    requires: BTreeMap<String, String>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, LockDependency>,
+}
+
+fn add_v2_packages(
+    graph: &mut DependencyGraph,
+    root: NodeId,
+    packages: &BTreeMap<String, LockPackage>,
+) {
+    let ids = packages
+        .iter()
+        .filter_map(|(path, package)| {
+            if path.is_empty() {
+                return Some((path.as_str(), root));
+            }
+            // links don't have a version, their target has its own entry
+            let version = package.version.as_deref()?;
+            Some((
+                path.as_str(),
+                graph.add_node(package_name(path, package), version),
+            ))
+        })
+        .collect::<HashMap<_, _>>();
+
+    for (path, package) in packages {
+        let Some(&from) = ids.get(path.as_str()) else {
+            continue;
+        };
+        let names = package
+            .dependencies
+            .keys()
+            .chain(package.dev_dependencies.keys())
+            .chain(package.optional_dependencies.keys())
+            .chain(package.peer_dependencies.keys());
+        for name in names {
+            if let Some(to) = resolve_package(path, name, &ids) {
+                graph.add_edge(from, to);
+            }
+        }
+    }
+}
+
+/// Gets the name of a package of a lockfile v2 from its path, unless it's explicitly declared (aliases).
+fn package_name<'a>(path: &'a str, package: &'a LockPackage) -> &'a str {
+    package.name.as_deref().unwrap_or_else(|| {
+        path.rsplit_once("node_modules/")
+            .map_or(path, |(_, name)| name)
+    })
+}
+
+/// Resolves a dependency the same way node does: looking for it in the `node_modules` folder of the package and then in the ones of its ancestors.
+fn resolve_package(path: &str, name: &str, ids: &HashMap<&str, NodeId>) -> Option<NodeId> {
+    let mut base = path;
+    loop {
+        let candidate = if base.is_empty() {
+            format!("node_modules/{name}")
+        } else {
+            format!("{base}/node_modules/{name}")
+        };
+        if let Some(id) = ids.get(candidate.as_str()) {
+            return Some(*id);
+        }
+        if base.is_empty() {
+            return None;
+        }
+        base = base
+            .rfind("/node_modules/")
+            .map_or("", |index| &base[..index]);
+    }
+}
+
+fn add_v1_dependencies<'a>(
+    graph: &mut DependencyGraph,
+    scopes: &mut Vec<&'a BTreeMap<String, LockDependency>>,
+    dependencies: &'a BTreeMap<String, LockDependency>,
+) {
+    scopes.push(dependencies);
+    for (name, dependency) in dependencies {
+        let from = graph.add_node(name.as_str(), dependency.version.as_str());
+        for required in dependency.requires.keys() {
+            let target = dependency
+                .dependencies
+                .get(required)
+                .or_else(|| scopes.iter().rev().find_map(|scope| scope.get(required)));
+            if let Some(target) = target {
+                let to = graph.add_node(required.as_str(), target.version.as_str());
+                graph.add_edge(from, to);
+            }
+        }
+        add_v1_dependencies(graph, scopes, &dependency.dependencies);
+    }
+    scopes.pop();
+}
+
+/// Wraps a `package-lock.json` parsing error, keeping the position informed by `serde_json`.
+fn parse_error(collector: &str, error: package_lock_json_parser::PackageLockJsonError) -> Error {
+    let package_lock_json_parser::PackageLockJsonError::ParseError(json_error) = &error;
+    let (line, column) = (json_error.line(), json_error.column());
+    Error::parse(collector, error).with_position(line, column)
+}
+
+fn json_parse_error(collector: &str, error: serde_json::Error) -> Error {
+    let (line, column) = (error.line(), error.column());
+    Error::parse(collector, error).with_position(line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(graph: &DependencyGraph, ids: impl Iterator<Item = NodeId>) -> Vec<&str> {
+        ids.map(|id| graph.node(id).unwrap().name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn it_builds_the_dependency_graph_of_v2_lockfiles() {
+        let content = r#"{
+            "name": "app",
+            "version": "1.0.0",
+            "lockfileVersion": 2,
+            "packages": {
+                "": { "name": "app", "version": "1.0.0", "dependencies": { "a": "^1.0.0" }, "devDependencies": { "b": "^1.0.0" } },
+                "node_modules/a": { "version": "1.0.0", "dependencies": { "c": "^1.0.0" } },
+                "node_modules/b": { "version": "1.0.0", "dev": true, "dependencies": { "c": "^2.0.0" } },
+                "node_modules/b/node_modules/c": { "version": "2.0.0", "dev": true },
+                "node_modules/c": { "version": "1.0.0" }
+            }
+        }"#;
+
+        let graph = Npm::default().get_dependency_graph(content).unwrap();
+        let a = graph.find("a", "1.0.0").unwrap();
+        let b = graph.find("b", "1.0.0").unwrap();
+
+        assert_eq!(names(&graph, graph.roots()), vec!["app"]);
+        assert_eq!(names(&graph, graph.direct_dependencies()), vec!["a", "b"]);
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "1.0.0").unwrap()),
+            vec![a]
+        );
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "2.0.0").unwrap()),
+            vec![b]
+        );
+    }
+
+    #[test]
+    fn it_builds_the_dependency_graph_of_v1_lockfiles() {
+        let content = r#"{
+            "name": "app",
+            "version": "1.0.0",
+            "lockfileVersion": 1,
+            "dependencies": {
+                "a": { "version": "1.0.0", "requires": { "c": "^1.0.0" } },
+                "b": {
+                    "version": "1.0.0",
+                    "requires": { "c": "^2.0.0" },
+                    "dependencies": { "c": { "version": "2.0.0" } }
+                },
+                "c": { "version": "1.0.0" }
+            }
+        }"#;
+
+        let graph = Npm::default().get_dependency_graph(content).unwrap();
+        let a = graph.find("a", "1.0.0").unwrap();
+        let b = graph.find("b", "1.0.0").unwrap();
+
+        assert_eq!(names(&graph, graph.direct_dependencies()), vec!["a", "b"]);
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "1.0.0").unwrap()),
+            vec![a]
+        );
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "2.0.0").unwrap()),
+            vec![b]
+        );
+        // top-level packages with dependents may be direct too
+        let lock = PackageLock::parse(NPM, content).unwrap();
+        assert_eq!(lock.is_direct(&graph, "a", "1.0.0"), Some(true));
+        assert_eq!(lock.is_direct(&graph, "c", "1.0.0"), None);
+        assert_eq!(lock.is_direct(&graph, "c", "2.0.0"), Some(false));
     }
 }
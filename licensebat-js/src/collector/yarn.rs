@@ -1,10 +1,13 @@
 use crate::{
-    collector::common::{retrieve_from_npm, NPM},
+    collector::common::{package_url, retrieve_from_npm, NPM},
     retriever::{self, npm::Retriever},
 };
 use licensebat_core::{
-    collector::RetrievedDependencyStreamResult, licrc::LicRc, Collector, Dependency, FileCollector,
+    collector::{Error, RetrievedDependencyStreamResult},
+    licrc::LicRc,
+    Collector, Dependency, DependencyGraph, FileCollector,
 };
+use std::collections::HashMap;
 use tracing::instrument;
 
 /// Yarn dependency [`FileCollector`] generic over [`Retriever`].
@@ -45,8 +48,11 @@ impl<R: Retriever> FileCollector for Yarn<R> {
         &self,
         dependency_file_content: &str,
         licrc: &LicRc,
-    ) -> RetrievedDependencyStreamResult {
-        let npm_deps = yarn_lock_parser::parse_str(dependency_file_content)?
+    ) -> RetrievedDependencyStreamResult<'_> {
+        let graph = self.get_dependency_graph(dependency_file_content)?;
+        let has_roots = graph.roots().next().is_some();
+        let npm_deps = yarn_lock_parser::parse_str(dependency_file_content)
+            .map_err(|e| Error::parse(self.get_name(), e))?
             .entries
             .into_iter()
             .map(|entry| Dependency {
@@ -54,9 +60,169 @@ impl<R: Retriever> FileCollector for Yarn<R> {
                 version: entry.version.to_owned(),
                 is_dev: None,
                 is_optional: None,
+                // yarn.lock files don't always declare where the packages have been resolved from
+                source: None,
+                purl: Some(package_url(entry.name, entry.version, None)),
+                is_direct: match graph.is_direct(entry.name, entry.version) {
+                    // without workspaces, packages other packages depend on may be direct dependencies too
+                    Some(false) if !has_roots => None,
+                    is_direct => is_direct,
+                },
             })
             .filter(|dep| licrc.filter_dependencies_before_retrieval(dep));
 
-        Ok(retrieve_from_npm(npm_deps, &self.retriever))
+        Ok(retrieve_from_npm(npm_deps, &self.retriever, licrc))
+    }
+
+    /// Builds the [`DependencyGraph`] of the `yarn.lock` file.
+    ///
+    /// Workspaces are considered roots.
+    /// Lockfiles without workspaces don't declare the dependencies of the project,
+    /// so the packages no other package depends on are considered its direct dependencies.
+    #[instrument(skip(self, dependency_file_content))]
+    fn get_dependency_graph(
+        &self,
+        dependency_file_content: &str,
+    ) -> Result<DependencyGraph, Error> {
+        let lockfile = yarn_lock_parser::parse_str(dependency_file_content)
+            .map_err(|e| Error::parse(self.get_name(), e))?;
+        let mut graph = DependencyGraph::new();
+        let mut descriptors = HashMap::new();
+        let ids = lockfile
+            .entries
+            .iter()
+            .map(|entry| {
+                let id = graph.add_node(entry.name, entry.version);
+                for descriptor in &entry.descriptors {
+                    descriptors.insert(*descriptor, id);
+                }
+                if entry
+                    .descriptors
+                    .iter()
+                    .any(|(_, range)| range.starts_with("workspace:"))
+                {
+                    graph.mark_root(id);
+                }
+                id
+            })
+            .collect::<Vec<_>>();
+
+        for (entry, from) in lockfile.entries.iter().zip(ids) {
+            for (name, range) in &entry.dependencies {
+                // yarn berry prefixes the descriptors with the protocol but not the dependencies
+                let to = descriptors.get(&(*name, *range)).copied().or_else(|| {
+                    descriptors
+                        .get(&(*name, format!("npm:{range}").as_str()))
+                        .copied()
+                });
+                if let Some(to) = to {
+                    graph.add_edge(from, to);
+                }
+            }
+        }
+
+        if graph.roots().next().is_none() {
+            let direct = (0..graph.nodes().len())
+                .filter(|id| graph.dependents_of(*id).next().is_none())
+                .collect::<Vec<_>>();
+            for id in direct {
+                graph.mark_direct(id);
+            }
+        } else {
+            graph.mark_direct_dependencies_of_roots();
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(graph: &DependencyGraph, ids: impl Iterator<Item = usize>) -> Vec<&str> {
+        ids.map(|id| graph.node(id).unwrap().name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn it_builds_the_dependency_graph() {
+        let content = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+a@^1.0.0:
+  version "1.0.0"
+  resolved "https://registry.yarnpkg.com/a/-/a-1.0.0.tgz"
+  integrity sha512-a
+  dependencies:
+    c "^1.0.0"
+
+b@^1.0.0:
+  version "1.0.0"
+  resolved "https://registry.yarnpkg.com/b/-/b-1.0.0.tgz"
+  integrity sha512-b
+  dependencies:
+    c "^1.0.0"
+
+c@^1.0.0:
+  version "1.0.0"
+  resolved "https://registry.yarnpkg.com/c/-/c-1.0.0.tgz"
+  integrity sha512-c
+"#;
+
+        let graph = Yarn::default().get_dependency_graph(content).unwrap();
+        let a = graph.find("a", "1.0.0").unwrap();
+        let b = graph.find("b", "1.0.0").unwrap();
+
+        assert_eq!(graph.roots().next(), None);
+        assert_eq!(names(&graph, graph.direct_dependencies()), vec!["a", "b"]);
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "1.0.0").unwrap()),
+            vec![a, b]
+        );
+    }
+
+    #[test]
+    fn workspaces_are_roots() {
+        let content = r#"# This file is generated by running "yarn install" inside your project.
+# Manual changes might be lost - proceed with caution!
+
+__metadata:
+  version: 6
+  cacheKey: 8
+
+"a@npm:^1.0.0":
+  version: 1.0.0
+  resolution: "a@npm:1.0.0"
+  dependencies:
+    c: ^1.0.0
+  checksum: aaaa
+  languageName: node
+  linkType: hard
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+  resolution: "app@workspace:."
+  dependencies:
+    a: ^1.0.0
+  languageName: unknown
+  linkType: soft
+
+"c@npm:^1.0.0":
+  version: 1.0.0
+  resolution: "c@npm:1.0.0"
+  checksum: cccc
+  languageName: node
+  linkType: hard
+"#;
+
+        let graph = Yarn::default().get_dependency_graph(content).unwrap();
+
+        assert_eq!(names(&graph, graph.roots()), vec!["app"]);
+        assert_eq!(names(&graph, graph.direct_dependencies()), vec!["a"]);
+        assert_eq!(
+            graph.introduced_by(graph.find("c", "1.0.0").unwrap()),
+            vec![graph.find("a", "1.0.0").unwrap()]
+        );
     }
 }
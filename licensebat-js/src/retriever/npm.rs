@@ -12,7 +12,10 @@ use futures::{
     future::{self, BoxFuture},
     Future, FutureExt, TryFutureExt,
 };
-use licensebat_core::{Dependency, RetrievedDependency};
+use licensebat_core::{
+    license::LicenseExpression, Dependency, FailureReason, LicenseSource, Provenance,
+    RetrievedDependency,
+};
 use reqwest::Client;
 use serde_json::Value;
 use tracing::instrument;
@@ -70,6 +73,7 @@ impl Retriever for Npm {
         self.client
             .get(url)
             .send()
+            .map(|response| response.and_then(reqwest::Response::error_for_status))
             .and_then(reqwest::Response::json)
             .map_ok(|metadata: Value| {
                 // get general license
@@ -93,7 +97,7 @@ impl Retriever for Npm {
                         md.get_licenses()
                     })
             })
-            .map_ok(move |licenses: Option<Vec<String>>| {
+            .map_ok(move |licenses: Option<LicenseExpression>| {
                 retrieved_dependency(&dep_clone, licenses, None)
             })
             .or_else(move |e| future::ok(retrieved_dependency(&dependency, None, Some(e))))
@@ -104,18 +108,21 @@ impl Retriever for Npm {
 
 fn retrieved_dependency(
     dependency: &Dependency,
-    licenses: Option<Vec<String>>,
+    licenses: Option<LicenseExpression>,
     error: Option<reqwest::Error>,
 ) -> RetrievedDependency {
-    let url = format!(
-        "https://www.npmjs.com/package/{}/v/{}",
-        dependency.name, dependency.version
-    );
+    let url = npm_url(dependency);
 
     let mut error_str: Option<String> = None;
+    let mut failure_reason = None;
 
     if let Some(error) = error {
         error_str = Some(error.to_string());
+        failure_reason = Some(if error.is_decode() {
+            FailureReason::ParseError
+        } else {
+            FailureReason::from_http_status(error.status().map(|s| s.as_u16()))
+        });
         tracing::error!(
             ?error,
             "Error while retrieving dependency for url {}: {}",
@@ -126,7 +133,14 @@ fn retrieved_dependency(
 
     tracing::debug!("Retrieved dependency for url {}", url);
 
-    RetrievedDependency::new(
+    let provenance = licenses.is_some().then(|| {
+        Provenance::declared(
+            LicenseSource::Registry,
+            Some(format!("https://registry.npmjs.org/{}", dependency.name)),
+        )
+    });
+
+    let mut retrieved = RetrievedDependency::new(
         dependency.name.clone(),
         dependency.version.clone(),
         crate::NPM.to_owned(),
@@ -137,5 +151,16 @@ fn retrieved_dependency(
         None,
         dependency.is_dev,
         dependency.is_optional,
+    );
+    retrieved.failure_reason = failure_reason.or(retrieved.failure_reason);
+    retrieved.provenance = provenance;
+    retrieved
+}
+
+/// Returns the url of the dependency in the npm website.
+pub(crate) fn npm_url(dependency: &Dependency) -> String {
+    format!(
+        "https://www.npmjs.com/package/{}/v/{}",
+        dependency.name, dependency.version
     )
 }
@@ -1,3 +1,4 @@
+use licensebat_core::license::LicenseExpression;
 use serde::de::{Error, MapAccess, SeqAccess, Visitor};
 use serde::Deserializer;
 use serde::{Deserialize, Serialize};
@@ -19,12 +20,15 @@ pub struct NpmMetadata {
 
 impl NpmMetadata {
     /// Returns the license/s of the dependency.
+    ///
+    /// The legacy `licenses` array means that the dependency can be used under any of them.
     #[must_use]
-    pub fn get_licenses(self) -> Option<Vec<String>> {
-        if self.licenses.is_some() {
-            self.licenses
+    pub fn get_licenses(self) -> Option<LicenseExpression> {
+        if let Some(licenses) = self.licenses {
+            LicenseExpression::any_of(licenses)
         } else {
-            self.license.map(|lic| vec![lic.replace('\"', "")])
+            self.license
+                .map(|lic| LicenseExpression::license(lic.replace('\"', "")))
         }
     }
 }
@@ -124,3 +128,22 @@ where
 {
     deserializer.deserialize_any(LicensesVisitor {})
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_licenses_array_means_any_of() {
+        let metadata: NpmMetadata = serde_json::from_value(serde_json::json!({
+            "name": "dep",
+            "version": "1.0.0",
+            "licenses": [{ "type": "MIT" }, { "type": "GPL-2.0-only" }],
+        }))
+        .unwrap();
+        assert_eq!(
+            metadata.get_licenses(),
+            LicenseExpression::any_of(["MIT", "GPL-2.0-only"])
+        );
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod integration_npm_retriever {
-    use licensebat_core::{Dependency, RetrievedDependency};
+    use licensebat_core::{license::LicenseExpression, Dependency, RetrievedDependency};
     use licensebat_js::retriever::{self, npm::Retriever};
 
     #[tokio::test]
@@ -13,7 +13,7 @@ mod integration_npm_retriever {
             ..Dependency::default()
         };
         let dep: RetrievedDependency = retriever.get_dependency(dependency).await;
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(dep.name, "exit");
     }
 }
@@ -13,11 +13,12 @@
 //! [`Retriever`]: crate::retriever::docs_rs::Retriever
 use crate::retriever::{self, docs_rs::Retriever};
 use cargo_lock::Package;
-use futures::FutureExt;
 use licensebat_core::{
-    collector::{RetrievedDependencyStream, RetrievedDependencyStreamResult},
+    collector::{Error, RetrievedDependencyStream, RetrievedDependencyStreamResult},
     licrc::LicRc,
-    Collector, Comment, Dependency, FileCollector, RetrievedDependency,
+    purl::{self, PackageUrl},
+    Collector, Comment, Dependency, DependencyGraph, FailureReason, FileCollector,
+    RetrievedDependency, Verdict,
 };
 use std::{str::FromStr, sync::Arc};
 use tracing::instrument;
@@ -71,24 +72,102 @@ impl<R: Retriever> FileCollector for Rust<R> {
         &self,
         dependency_file_content: &str,
         licrc: &LicRc,
-    ) -> RetrievedDependencyStreamResult {
-        let lockfile = cargo_lock::Lockfile::from_str(dependency_file_content)?;
-        let futures = lockfile
+    ) -> RetrievedDependencyStreamResult<'_> {
+        let lockfile = parse_lockfile(dependency_file_content)?;
+        let graph = build_graph(&lockfile);
+        // without workspace members we can't know which dependencies are direct
+        let has_roots = graph.roots().next().is_some();
+        let dependencies = lockfile
             .packages
             .into_iter()
-            .filter(|p| {
-                licrc.filter_dependencies_before_retrieval(&Dependency {
-                    name: p.name.to_string(),
-                    version: p.version.to_string(),
-                    is_dev: None,
-                    is_optional: None,
-                })
+            .map(|p| {
+                let mut dependency = Dependency::new(p.name.to_string(), p.version.to_string());
+                if has_roots {
+                    dependency.is_direct = graph.is_direct(&dependency.name, &dependency.version);
+                }
+                dependency.source = p.source.as_ref().map(ToString::to_string);
+                dependency.purl = Some(package_url(&p));
+                (p, dependency)
             })
-            .map(|p| get_dependency(p, &self.retriever).boxed())
-            .collect();
+            .filter(|(_, dependency)| licrc.filter_dependencies_before_retrieval(dependency));
 
-        Ok(RetrievedDependencyStream::new(futures))
+        let retriever = &self.retriever;
+        Ok(RetrievedDependencyStream::retrieve(
+            dependencies,
+            licrc,
+            crate::RUST,
+            |_| None,
+            move |p| get_dependency(p, retriever),
+        ))
     }
+
+    /// Builds the [`DependencyGraph`] of the `Cargo.lock` file.
+    ///
+    /// Packages without source are workspace members or path dependencies, and `Cargo.lock` doesn't tell them apart.
+    /// Only the ones that no other package without source depends on are considered roots,
+    /// so a path dependency of the project is a direct dependency instead of a root.
+    /// A workspace member used by another member won't be a root either.
+    #[instrument(skip(self, dependency_file_content))]
+    fn get_dependency_graph(
+        &self,
+        dependency_file_content: &str,
+    ) -> Result<DependencyGraph, Error> {
+        parse_lockfile(dependency_file_content).map(|lockfile| build_graph(&lockfile))
+    }
+}
+
+fn build_graph(lockfile: &cargo_lock::Lockfile) -> DependencyGraph {
+    let mut graph = DependencyGraph::new();
+    let mut local = vec![];
+    for package in &lockfile.packages {
+        let from = graph.add_node(package.name.as_str(), package.version.to_string());
+        if package.source.is_none() {
+            local.push(from);
+        }
+        for dependency in &package.dependencies {
+            let to = graph.add_node(dependency.name.as_str(), dependency.version.to_string());
+            graph.add_edge(from, to);
+        }
+    }
+    let roots = local
+        .iter()
+        .copied()
+        .filter(|id| {
+            !graph
+                .dependents_of(*id)
+                .any(|dependent| local.contains(&dependent))
+        })
+        .collect::<Vec<_>>();
+    for root in roots {
+        graph.mark_root(root);
+    }
+    graph.mark_direct_dependencies_of_roots();
+    graph
+}
+
+/// Builds the Package URL of a package of the `Cargo.lock` file.
+fn package_url(package: &Package) -> String {
+    let purl = PackageUrl::new(purl::CARGO, package.name.as_str())
+        .with_version(package.version.to_string());
+    match &package.source {
+        Some(source) if source.is_git() => {
+            let precise = source
+                .precise()
+                .map(|p| format!("@{p}"))
+                .unwrap_or_default();
+            purl.with_qualifier("vcs_url", format!("git+{}{precise}", source.url()))
+        }
+        Some(source) if source.is_registry() && !source.is_default_registry() => {
+            purl.with_qualifier("repository_url", source.url().as_str())
+        }
+        _ => purl,
+    }
+    .into()
+}
+
+fn parse_lockfile(dependency_file_content: &str) -> Result<cargo_lock::Lockfile, Error> {
+    cargo_lock::Lockfile::from_str(dependency_file_content)
+        .map_err(|e| Error::parse(crate::RUST, e))
 }
 
 async fn get_dependency<R: Retriever>(package: Package, retriever: &R) -> RetrievedDependency {
@@ -98,12 +177,8 @@ async fn get_dependency<R: Retriever>(package: Package, retriever: &R) -> Retrie
             #[allow(clippy::if_same_then_else)]
             if source.is_default_registry() {
                 // this is the only one supported for now
-                let dependency = licensebat_core::Dependency {
-                    name: package.name.to_string(),
-                    version: package.version.to_string(),
-                    is_dev: None,
-                    is_optional: None,
-                };
+                let dependency =
+                    Dependency::new(package.name.to_string(), package.version.to_string());
                 return retriever.get_dependency(dependency).await;
             } else if source.is_remote_registry() {
                 // remote registry
@@ -135,13 +210,23 @@ async fn get_dependency<R: Retriever>(package: Package, retriever: &R) -> Retrie
             dependency_type: crate::RUST.to_owned(),
             validated: false,
             is_valid: false,
+            verdict: Verdict::Deny,
             is_ignored: false,
+            is_exception_expired: false,
             error: Some("Crate type not Supported".to_owned()),
+            failure_reason: Some(FailureReason::UnsupportedSource),
             licenses:  None,
-            comment: Some(Comment::removable("Git, Local and Remote registries are not supported yet. We're working on it. We're marking this as invalid by default so you can check the validity of the license. Consider adding this dependency to the ignored list in the .licrc configuration file if you trust the source.")),
+            comment: Some(Comment::removable("Git, Local and Remote registries are not supported yet. We're working on it. We're marking this as invalid by default so you can check the validity of the license. Consider declaring its license in the overrides of the .licrc configuration file, or adding this dependency to the ignored list, if you trust the source.")),
+            provenance: None,
             suggested_licenses: None,
             is_dev: None,
             is_optional: None,
+            is_direct: None,
+            source: None,
+            purl: None,
+            is_overridden: false,
+            category: None,
+            policy: None,
         }
 }
 
@@ -150,9 +235,9 @@ mod tests {
     use super::*;
     use futures::{
         future::{ready, BoxFuture},
-        StreamExt,
+        FutureExt, StreamExt,
     };
-    use licensebat_core::Dependency;
+    use licensebat_core::{license::LicenseExpression, Dependency, LicenseSource};
 
     #[derive(Debug)]
     struct MockRetriever;
@@ -189,15 +274,15 @@ mod tests {
         checksum = "2a60c7ce501c71e03a9c9c0d35b861413ae925bd979cc7a4e30d060069aaac8d"
         "#;
 
-        let mut deps = rust
-            .get_dependencies(&lock_content, &get_licrc())
+        let deps = rust
+            .get_dependencies(lock_content, &get_licrc())
             .unwrap()
             .collect::<Vec<_>>()
             .await;
 
         assert_eq!(deps.len(), 1);
 
-        let dep = deps[0].as_mut().fuse().await;
+        let dep = &deps[0];
 
         assert_eq!(rust.get_name(), "rust");
         assert_eq!(rust.get_dependency_filename(), "Cargo.lock");
@@ -216,15 +301,15 @@ mod tests {
         checksum = "2a60c7ce501c71e03a9c9c0d35b861413ae925bd979cc7a4e30d060069aaac8d"
         "#;
 
-        let mut deps = rust
-            .get_dependencies(&lock_content, &get_licrc())
+        let deps = rust
+            .get_dependencies(lock_content, &get_licrc())
             .unwrap()
             .collect::<Vec<_>>()
             .await;
 
         assert_eq!(deps.len(), 1);
 
-        let dep = deps[0].as_mut().fuse().await;
+        let dep = &deps[0];
 
         assert_eq!(rust.get_name(), "rust");
         assert_eq!(rust.get_dependency_filename(), "Cargo.lock");
@@ -232,6 +317,40 @@ mod tests {
         assert_eq!(dep.version, "3.0.0-beta.4");
     }
 
+    #[tokio::test]
+    async fn overrides_skip_retrieval() {
+        let rust = build_collector();
+        let lock_content = r#"
+        [[package]]
+        name = "mime"
+        version = "3.0.0"
+        source = "git+https://github.com/rust-lang/crates.io-index"
+        "#;
+
+        let mut licrc = get_licrc();
+        licrc.dependencies.overrides =
+            Some([("mime".to_owned(), "mit/apache-2.0".to_owned())].into());
+
+        let deps = rust
+            .get_dependencies(lock_content, &licrc)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+
+        let dep = &deps[0];
+
+        assert!(dep.is_overridden);
+        assert_eq!(
+            dep.provenance.as_ref().map(|p| p.source),
+            Some(LicenseSource::Override)
+        );
+        assert!(dep.is_valid);
+        assert_eq!(
+            dep.licenses,
+            Some(LicenseExpression::license("MIT OR Apache-2.0"))
+        );
+    }
+
     #[tokio::test]
     async fn git_is_not_implemented() {
         let rust = build_collector();
@@ -243,19 +362,175 @@ mod tests {
         checksum = "2a60c7ce501c71e03a9c9c0d35b861413ae925bd979cc7a4e30d060069aaac8d"
         "#;
 
-        let mut deps = rust
-            .get_dependencies(&lock_content, &get_licrc())
+        let deps = rust
+            .get_dependencies(lock_content, &get_licrc())
             .unwrap()
             .collect::<Vec<_>>()
             .await;
 
         assert_eq!(deps.len(), 1);
 
-        let dep = deps[0].as_mut().fuse().await;
+        let dep = &deps[0];
 
         assert_eq!(rust.get_name(), "rust");
         assert_eq!(rust.get_dependency_filename(), "Cargo.lock");
         assert_eq!(dep.name, "mime");
         assert_eq!(dep.version, "3.0.0");
     }
+
+    #[test]
+    fn it_builds_the_dependency_graph() {
+        let rust = build_collector();
+        let lock_content = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["mime", "reqwest"]
+
+        [[package]]
+        name = "mime"
+        version = "0.3.16"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "reqwest"
+        version = "0.11.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["mime"]
+        "#;
+
+        let graph = rust.get_dependency_graph(lock_content).unwrap();
+        let app = graph.find("app", "0.1.0").unwrap();
+        let mime = graph.find("mime", "0.3.16").unwrap();
+        let reqwest = graph.find("reqwest", "0.11.0").unwrap();
+
+        assert_eq!(graph.roots().collect::<Vec<_>>(), vec![app]);
+        assert_eq!(
+            graph.direct_dependencies().collect::<Vec<_>>(),
+            vec![mime, reqwest]
+        );
+        assert_eq!(
+            graph.dependents_of(mime).collect::<Vec<_>>(),
+            vec![app, reqwest]
+        );
+    }
+
+    #[test]
+    fn path_dependencies_are_not_roots() {
+        let rust = build_collector();
+        let lock_content = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["local-utils"]
+
+        [[package]]
+        name = "local-utils"
+        version = "0.2.0"
+        dependencies = ["mime"]
+
+        [[package]]
+        name = "mime"
+        version = "0.3.16"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        "#;
+
+        let graph = rust.get_dependency_graph(lock_content).unwrap();
+        let app = graph.find("app", "0.1.0").unwrap();
+        let local_utils = graph.find("local-utils", "0.2.0").unwrap();
+        let mime = graph.find("mime", "0.3.16").unwrap();
+
+        assert_eq!(graph.roots().collect::<Vec<_>>(), vec![app]);
+        assert_eq!(graph.is_direct("local-utils", "0.2.0"), Some(true));
+        assert_eq!(graph.is_direct("mime", "0.3.16"), Some(false));
+        assert_eq!(graph.introduced_by(mime), vec![local_utils]);
+    }
+
+    #[tokio::test]
+    async fn dependencies_know_if_they_are_direct() {
+        let rust = build_collector();
+        let lock_content = r#"
+        version = 3
+
+        [[package]]
+        name = "app"
+        version = "0.1.0"
+        dependencies = ["reqwest"]
+
+        [[package]]
+        name = "mime"
+        version = "0.3.16"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "reqwest"
+        version = "0.11.0"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+        dependencies = ["mime"]
+        "#;
+
+        let mut licrc = get_licrc();
+        licrc.dependencies.overrides = Some([("mime".to_owned(), "MIT".to_owned())].into());
+
+        let mut deps = rust
+            .get_dependencies(lock_content, &licrc)
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        deps.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let is_direct = deps
+            .iter()
+            .map(|dep| (dep.name.as_str(), dep.is_direct))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            is_direct,
+            vec![
+                ("app", None),
+                ("mime", Some(false)),
+                ("reqwest", Some(true))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn dependencies_have_a_source_and_a_package_url() {
+        let rust = build_collector();
+        let lock_content = r#"
+        [[package]]
+        name = "mime"
+        version = "0.3.16"
+        source = "registry+https://github.com/rust-lang/crates.io-index"
+
+        [[package]]
+        name = "foo"
+        version = "0.1.0"
+        source = "git+https://github.com/acme/foo?branch=main#abc123"
+        "#;
+
+        let mut deps = rust
+            .get_dependencies(lock_content, &get_licrc())
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        deps.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(
+            deps[0].source.as_deref(),
+            Some("git+https://github.com/acme/foo?branch=main#abc123")
+        );
+        assert_eq!(
+            deps[0].purl.as_deref(),
+            Some("pkg:cargo/foo@0.1.0?vcs_url=git%2Bhttps:%2F%2Fgithub.com%2Facme%2Ffoo%40abc123")
+        );
+        assert_eq!(
+            deps[1].source.as_deref(),
+            Some("registry+https://github.com/rust-lang/crates.io-index")
+        );
+        assert_eq!(deps[1].purl.as_deref(), Some("pkg:cargo/mime@0.3.16"));
+    }
 }
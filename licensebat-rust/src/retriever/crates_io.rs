@@ -10,11 +10,14 @@
 
 #![allow(deprecated)]
 
-use super::utils::crates_io_retrieved_dependency;
+use super::utils::{crates_io_retrieved_dependency, failure_reason};
 use crate::retriever::docs_rs::Retriever as DocsRetriever;
 use askalono::Store;
 use futures::{future::BoxFuture, Future, FutureExt, TryFutureExt};
-use licensebat_core::{Dependency, RetrievedDependency};
+use licensebat_core::{
+    license::LicenseExpression, licrc::DEFAULT_MIN_DETECTION_CONFIDENCE, Dependency, FailureReason,
+    LicenseSource, Provenance, RetrievedDependency,
+};
 use reqwest::Client;
 use serde_json::Value;
 use std::sync::Arc;
@@ -52,6 +55,7 @@ pub trait Retriever: Send + Sync + std::fmt::Debug {
 pub struct CratesIo {
     client: Client,
     store: Arc<Option<Store>>,
+    min_detection_confidence: f32,
 }
 
 impl CratesIo {
@@ -59,7 +63,23 @@ impl CratesIo {
     /// If you want to reuse a [`reqwest::Client`] pool consider using the [`CratesIo::new`] method.
     #[must_use]
     pub const fn new(client: Client, store: Arc<Option<Store>>) -> Self {
-        Self { client, store }
+        Self {
+            client,
+            store,
+            min_detection_confidence: DEFAULT_MIN_DETECTION_CONFIDENCE,
+        }
+    }
+
+    /// Sets the minimum confidence needed to trust a license detected by analyzing the text of a license file,
+    /// as explained in [`LicRcBehavior::min_detection_confidence`].
+    ///
+    /// It's set to [`DEFAULT_MIN_DETECTION_CONFIDENCE`] by default.
+    ///
+    /// [`LicRcBehavior::min_detection_confidence`]: licensebat_core::licrc::LicRcBehavior::min_detection_confidence
+    #[must_use]
+    pub const fn with_min_detection_confidence(mut self, min_detection_confidence: f32) -> Self {
+        self.min_detection_confidence = min_detection_confidence;
+        self
     }
 }
 
@@ -76,6 +96,7 @@ impl Clone for CratesIo {
         Self {
             client: self.client.clone(),
             store: self.store.clone(),
+            min_detection_confidence: self.min_detection_confidence,
         }
     }
 }
@@ -92,6 +113,7 @@ impl std::fmt::Debug for CratesIo {
                     &"None"
                 },
             )
+            .field("min_detection_confidence", &self.min_detection_confidence)
             .finish()
     }
 }
@@ -109,6 +131,7 @@ impl Retriever for CratesIo {
         let dep_clone = dependency.clone();
         let client = self.client.clone();
         let store = self.store.clone();
+        let min_detection_confidence = self.min_detection_confidence;
 
         async move {
             let metadata: Value = client
@@ -116,6 +139,7 @@ impl Retriever for CratesIo {
                 .header("User-Agent", "licensebat-cli (licensebat.com)")
                 .send()
                 .await?
+                .error_for_status()?
                 .json()
                 .await?;
 
@@ -127,18 +151,19 @@ impl Retriever for CratesIo {
                     // or by a generic `non-standard` license.
                     if license == "non-standard" {
                         // we're going to use the docs.rs retriever here
-                        let docs_rs = super::docs_rs::DocsRs::new(client, store);
+                        let docs_rs = super::docs_rs::DocsRs::new(client, store)
+                            .with_min_detection_confidence(min_detection_confidence);
                         docs_rs.get_dependency(dependency).await
                     } else {
-                        // TODO: ADD SUPPORT FOR MULTIPLE LICENSES by using the spdx crate
-                        let licenses = vec![license.to_string()];
+                        // the license field is a single SPDX expression (i.e. `MIT OR Apache-2.0`)
                         crates_io_retrieved_dependency(
                             &dependency,
-                            Some(licenses),
+                            Some(LicenseExpression::license(license)),
                             None,
                             None,
                             None,
                         )
+                        .with_provenance(Provenance::declared(LicenseSource::Registry, Some(url)))
                     }
                 } else {
                     crates_io_retrieved_dependency(
@@ -148,6 +173,7 @@ impl Retriever for CratesIo {
                         None,
                         None,
                     )
+                    .with_failure_reason(FailureReason::NoLicenseDeclared)
                 }
             };
             Ok::<_, anyhow::Error>(retrieved_dependency)
@@ -155,6 +181,7 @@ impl Retriever for CratesIo {
         .unwrap_or_else(move |e| {
             let error = e.to_string();
             crates_io_retrieved_dependency(&dep_clone, None, Some(error.as_str()), None, None)
+                .with_failure_reason(failure_reason(&e))
         })
         .boxed()
     }
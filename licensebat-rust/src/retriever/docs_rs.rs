@@ -7,10 +7,13 @@
 //! [`Retriever`]: crate::retriever::docs_rs::Retriever
 //! [Docs.rs website]: https://docs.rs/
 
-use super::utils::crates_io_retrieved_dependency;
+use super::utils::{crates_io_retrieved_dependency, failure_reason};
 use askalono::{Store, TextData};
 use futures::{future::BoxFuture, Future, FutureExt, TryFutureExt};
-use licensebat_core::{Dependency, RetrievedDependency};
+use licensebat_core::{
+    license::LicenseExpression, licrc::DEFAULT_MIN_DETECTION_CONFIDENCE, Dependency, FailureReason,
+    LicenseSource, Provenance, RetrievedDependency,
+};
 use reqwest::Client;
 use std::{string::String, sync::Arc};
 use thiserror::Error;
@@ -39,6 +42,7 @@ pub trait Retriever: Send + Sync + std::fmt::Debug {
 pub struct DocsRs {
     client: Client,
     store: Arc<Option<Store>>,
+    min_detection_confidence: f32,
 }
 
 impl DocsRs {
@@ -46,7 +50,23 @@ impl DocsRs {
     /// If you want to reuse a [`reqwest::Client`] pool consider using the [`DocsRs::new`] method.
     #[must_use]
     pub const fn new(client: Client, store: Arc<Option<Store>>) -> Self {
-        Self { client, store }
+        Self {
+            client,
+            store,
+            min_detection_confidence: DEFAULT_MIN_DETECTION_CONFIDENCE,
+        }
+    }
+
+    /// Sets the minimum confidence needed to trust a license detected by analyzing the text of a license file,
+    /// as explained in [`LicRcBehavior::min_detection_confidence`].
+    ///
+    /// It's set to [`DEFAULT_MIN_DETECTION_CONFIDENCE`] by default.
+    ///
+    /// [`LicRcBehavior::min_detection_confidence`]: licensebat_core::licrc::LicRcBehavior::min_detection_confidence
+    #[must_use]
+    pub const fn with_min_detection_confidence(mut self, min_detection_confidence: f32) -> Self {
+        self.min_detection_confidence = min_detection_confidence;
+        self
     }
 }
 
@@ -63,6 +83,7 @@ impl Clone for DocsRs {
         Self {
             client: self.client.clone(),
             store: self.store.clone(),
+            min_detection_confidence: self.min_detection_confidence,
         }
     }
 }
@@ -79,6 +100,7 @@ impl std::fmt::Debug for DocsRs {
                     &"None"
                 },
             )
+            .field("min_detection_confidence", &self.min_detection_confidence)
             .finish()
     }
 }
@@ -94,6 +116,7 @@ impl Retriever for DocsRs {
         let dep_clone = dependency.clone();
         let client = self.client.clone();
         let store = self.store.clone();
+        let min_detection_confidence = self.min_detection_confidence;
 
         async move {
             let html = client
@@ -101,6 +124,7 @@ impl Retriever for DocsRs {
                 .header("User-Agent", "licensebat-cli (licensebat.com)")
                 .send()
                 .await?
+                .error_for_status()?
                 .text()
                 .await?;
 
@@ -135,15 +159,17 @@ impl Retriever for DocsRs {
                         match key.as_ref() {
                             "license" => {
                                  // TODO: SUPPORT FOR MULTIPLE LICS HERE
-                                crates_io_retrieved_dependency(&dependency, Some(vec![value]), None, None, None)
+                                crates_io_retrieved_dependency(&dependency, Some(LicenseExpression::license(value)), None, None, None)
+                                    .with_provenance(Provenance::declared(LicenseSource::Manifest, Some(cargo_toml_url)))
                             }
                             "license-file" => {
-                                get_retrieved_dependency_from_license_file(store, crate_url, value, client, &dependency).await
+                                get_retrieved_dependency_from_license_file(store, crate_url, value, client, &dependency, min_detection_confidence).await
                             }
                             // this should never happen!
                             _ => {
                                 tracing::error!("Unknown license key: {}", key);
                                 crates_io_retrieved_dependency(&dependency, None, Some("Unexpected license key while parsing cargo.toml"), None, None)
+                                    .with_failure_reason(FailureReason::ParseError)
                             }
                         }
                     } else {
@@ -155,6 +181,7 @@ impl Retriever for DocsRs {
                             &dependency.version,
                         );
                         crates_io_retrieved_dependency(&dependency, None, Some(user_error), None, None)
+                            .with_failure_reason(FailureReason::NoLicenseDeclared)
                     }
                 }
                 Err(e) => {
@@ -164,6 +191,7 @@ impl Retriever for DocsRs {
                         None,
                         Some("Error trying to parse docs.rs"), None, None
                     )
+                    .with_failure_reason(FailureReason::ParseError)
                 }
             };
 
@@ -171,6 +199,7 @@ impl Retriever for DocsRs {
         }.unwrap_or_else(move |e| {
                 let error = e.to_string();
                 crates_io_retrieved_dependency(&dep_clone, None, Some(error.as_str()), None, None)
+                    .with_failure_reason(failure_reason(&e))
             })
             .boxed()
     }
@@ -184,26 +213,38 @@ fn docs_rs_url(dependency_name: &str, dependency_version: &str) -> String {
 /// Returns a `RetrievedDependency` by looking into the Docs.rs declared license file.
 /// This function will use `askalono::Store` to determine the kind of license.
 /// Note that in the comments of the `RetrievedDependency` there will be a `Comment` with the % score.
+/// If the score is lower than `min_detection_confidence`, the dependency will be flagged for review.
 async fn get_retrieved_dependency_from_license_file(
     store: Arc<Option<Store>>,
     crate_url: String,
     license: String,
     client: Client,
     dependency: &Dependency,
+    min_detection_confidence: f32,
 ) -> RetrievedDependency {
     if let Some(store) = store.as_ref() {
         let license_url = format!("{crate_url}{license}");
         if let Ok((license, score)) = get_license_from_docs_rs(&client, store, &license_url).await {
-            crates_io_retrieved_dependency(
+            let is_confident = score >= min_detection_confidence;
+            // the comment of a dependency flagged for review already tells the score
+            let retrieved = crates_io_retrieved_dependency(
                 dependency,
-                Some(vec![license.clone()]),
+                Some(LicenseExpression::license(license.clone())),
                 None,
-                Some(format!(
-                    "Our score for this license is {:.2}%.",
-                    score * 100.0
-                )),
-                Some(vec![(license, score)]),
+                is_confident
+                    .then(|| format!("Our score for this license is {:.2}%.", score * 100.0)),
+                Some(vec![(license.clone(), score)]),
             )
+            .with_provenance(Provenance::askalono(license_url, score));
+            if !is_confident {
+                retrieved.flagged_for_review(format!(
+                    "Our score for **{license}** is **{:.2}%**, lower than the minimum detection confidence (**{:.2}%**). Please review it.",
+                    score * 100.0,
+                    min_detection_confidence * 100.0
+                ))
+            } else {
+                retrieved
+            }
         } else {
             crates_io_retrieved_dependency(
                 dependency,
@@ -214,6 +255,7 @@ async fn get_retrieved_dependency_from_license_file(
                 None,
                 None,
             )
+            .with_failure_reason(FailureReason::NoLicenseDeclared)
         }
     } else {
         tracing::error!("No askalono store present in Rust docs.rs retriever");
@@ -224,6 +266,7 @@ async fn get_retrieved_dependency_from_license_file(
             None,
             None,
         )
+        .with_failure_reason(FailureReason::Other)
     }
 }
 
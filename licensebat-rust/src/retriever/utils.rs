@@ -1,10 +1,12 @@
-use licensebat_core::{Comment, Dependency, RetrievedDependency};
+use licensebat_core::{
+    license::LicenseExpression, Comment, Dependency, FailureReason, RetrievedDependency,
+};
 use tracing::instrument;
 
 #[instrument(level = "debug")]
 pub fn crates_io_retrieved_dependency(
     dependency: &Dependency,
-    licenses: Option<Vec<String>>,
+    licenses: Option<LicenseExpression>,
     error: Option<&str>,
     comment: Option<String>,
     suggested_licenses: Option<Vec<(String, f32)>>,
@@ -27,3 +29,12 @@ pub fn crates_io_retrieved_dependency(
         dependency.is_optional,
     )
 }
+
+/// Returns the [`FailureReason`] of an error raised while retrieving a dependency.
+pub fn failure_reason(error: &anyhow::Error) -> FailureReason {
+    match error.downcast_ref::<reqwest::Error>() {
+        Some(error) if error.is_decode() => FailureReason::ParseError,
+        Some(error) => FailureReason::from_http_status(error.status().map(|s| s.as_u16())),
+        None => FailureReason::Other,
+    }
+}
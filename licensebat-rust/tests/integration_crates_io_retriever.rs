@@ -1,7 +1,7 @@
 #![allow(deprecated)]
 #[cfg(test)]
 mod integration_crates_io_retriever {
-    use licensebat_core::{Dependency, RetrievedDependency};
+    use licensebat_core::{license::LicenseExpression, Dependency, RetrievedDependency};
     use licensebat_rust::retriever::{self, crates_io::Retriever};
 
     const LICENSE_CACHE: &[u8] = std::include_bytes!("../../licensebat-cli/license-cache.bin.zstd");
@@ -19,7 +19,7 @@ mod integration_crates_io_retriever {
             .get_dependency(Dependency::new("futurify", "0.2.0"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(dep.name, "futurify");
     }
 
@@ -55,7 +55,7 @@ mod integration_crates_io_retriever {
             .get_dependency(Dependency::new("futurify", "0.2.0"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(dep.name, "futurify");
     }
 
@@ -67,7 +67,7 @@ mod integration_crates_io_retriever {
             .get_dependency(Dependency::new("ring", "0.16.20"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["OpenSSL".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("OpenSSL")), dep.licenses);
         assert_eq!(dep.name, "ring");
         assert_eq!(dep.comment.map(|c| c.text.contains("score")), Some(true));
     }
@@ -80,7 +80,7 @@ mod integration_crates_io_retriever {
             .get_dependency(Dependency::new("ring", "0.17.0-alpha.9"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["OpenSSL".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("OpenSSL")), dep.licenses);
         assert_eq!(dep.name, "ring");
         assert_eq!(dep.comment.map(|c| c.text.contains("score")), Some(true));
     }
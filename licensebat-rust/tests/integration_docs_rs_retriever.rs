@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod integration_docs_rs_retriever {
-    use licensebat_core::{Dependency, RetrievedDependency};
+    use licensebat_core::{license::LicenseExpression, Dependency, RetrievedDependency};
     use licensebat_rust::retriever::{self, docs_rs::Retriever};
 
     const LICENSE_CACHE: &[u8] = std::include_bytes!("../../licensebat-cli/license-cache.bin.zstd");
@@ -17,7 +17,7 @@ mod integration_docs_rs_retriever {
         let dep: RetrievedDependency = retriever
             .get_dependency(Dependency::new("futurify", "0.2.0"))
             .await;
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
         assert_eq!(dep.name, "futurify");
         assert!(dep.suggested_licenses.is_none());
@@ -57,7 +57,7 @@ mod integration_docs_rs_retriever {
             .get_dependency(Dependency::new("futurify", "0.2.0"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["MIT".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("MIT")), dep.licenses);
         assert_eq!(dep.name, "futurify");
         assert!(dep.suggested_licenses.is_none());
     }
@@ -70,7 +70,7 @@ mod integration_docs_rs_retriever {
             .get_dependency(Dependency::new("ring", "0.16.20"))
             .await;
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
-        assert_eq!(Some(vec!["OpenSSL".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("OpenSSL")), dep.licenses);
         assert_eq!(dep.name, "ring");
         assert_eq!(dep.comment.map(|c| c.text.contains("score")), Some(true));
         let suggested_licenses = dep.suggested_licenses.unwrap();
@@ -86,7 +86,7 @@ mod integration_docs_rs_retriever {
         let dep: RetrievedDependency = retriever
             .get_dependency(Dependency::new("ring", "0.17.0-alpha.9"))
             .await;
-        assert_eq!(Some(vec!["OpenSSL".to_string()]), dep.licenses);
+        assert_eq!(Some(LicenseExpression::license("OpenSSL")), dep.licenses);
         assert_eq!(&dep.dependency_type, licensebat_rust::RUST);
         assert_eq!(dep.name, "ring");
         assert_eq!(dep.comment.map(|c| c.text.contains("score")), Some(true));